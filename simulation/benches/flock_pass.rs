@@ -0,0 +1,74 @@
+//! Times `Flock::flock`, which walks the neighbours once, against the three rules as
+//! separate passes. Run with `cargo bench`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use flocking_simulation::flocking::{Boid, Flock};
+use flocking_simulation::rng::Rng;
+use flocking_simulation::vector2::Vector2;
+
+#[path = "../tests/reference/mod.rs"]
+mod reference;
+
+use reference::{reference_align, reference_cohesion, reference_separate};
+
+const AREA_SIZE: f32 = 1000.0;
+const ROUNDS: u32 = 200;
+
+// -----------------------------------------------------------------------------------------
+
+fn random_flock(count: usize, seed: u64) -> Flock {
+    let mut rng = Rng::new(seed);
+
+    let boids = (0..count)
+        .map(|_| {
+            let position = Vector2::new(rng.next_f32() * AREA_SIZE, rng.next_f32() * AREA_SIZE);
+            let angle = rng.next_f32() * std::f32::consts::PI * 2.0;
+            let velocity = Vector2::new(angle.cos(), angle.sin());
+            Boid::with_velocity(&position, &velocity)
+        })
+        .collect();
+
+    Flock::from_boids(boids, AREA_SIZE)
+}
+
+/// Average time of one `pass` over every boid.
+fn time<F: FnMut() -> Vector2<f32>>(mut pass: F) -> Duration {
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        black_box(pass());
+    }
+    start.elapsed() / ROUNDS
+}
+
+fn main() {
+    for &count in &[100, 300, 500] {
+        let flock = random_flock(count, 0x2545f491);
+        let neighbours: Vec<usize> = (0..count).collect();
+        let boids = flock.boids();
+        let params = flock.params();
+
+        let fused = time(|| {
+            let mut sum = Vector2::zero();
+            for index in 0..count {
+                sum += flock.flock(index, &neighbours, &[]);
+            }
+            sum
+        });
+        let separate = time(|| {
+            let mut sum = Vector2::zero();
+            for index in 0..count {
+                sum += reference_cohesion(boids, index, params) * params.cohesion_weight;
+                sum += reference_separate(boids, index, params) * params.separate_weight;
+                sum += reference_align(boids, index, params) * params.align_weight;
+            }
+            sum
+        });
+
+        println!(
+            "{} boids: fused {:?}, separate {:?} per pass",
+            count, fused, separate
+        );
+    }
+}
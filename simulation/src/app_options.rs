@@ -0,0 +1,98 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Deserializer};
+
+use crate::boundary::BoundaryMode;
+use crate::flock_params::FlockParams;
+
+// -----------------------------------------------------------------------------------------
+
+/// Smallest `geometry_capacity`: the renderer draws an obstacle circle, 72 vertices, in one
+/// batch.
+pub const MIN_GEOMETRY_CAPACITY: usize = 72;
+
+// -----------------------------------------------------------------------------------------
+
+/// Settings of the wasm app, given to its constructor as a JS object. Missing fields keep
+/// their defaults, unknown ones are rejected.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AppOptions {
+    /// CSS selector of the canvas to draw on.
+    pub canvas: String,
+    /// RGBA color, e.g. `0x222222ff`.
+    pub clear_color: u32,
+    /// Images the species can be drawn with. The default species uses the first one.
+    pub boid_textures: Vec<String>,
+    pub predator_texture: String,
+
+    pub boids: usize,
+    pub seed: u64,
+    #[serde(deserialize_with = "from_str")]
+    pub boundary_mode: BoundaryMode,
+    pub params: FlockParams,
+
+    /// Vertices the geometry renderer batches before flushing, at least
+    /// `MIN_GEOMETRY_CAPACITY`.
+    pub geometry_capacity: usize,
+    /// Sprites the rectangle renderer batches before flushing. Larger flocks are drawn in
+    /// several batches.
+    pub sprite_capacity: usize,
+}
+
+impl Default for AppOptions {
+    fn default() -> Self {
+        Self {
+            canvas: "#canvas".to_string(),
+            clear_color: 0x222222ff,
+            boid_textures: vec!["static/fish.png".to_string(), "static/boid.png".to_string()],
+            predator_texture: "static/predator.png".to_string(),
+
+            boids: 50,
+            seed: 0x5eed,
+            boundary_mode: BoundaryMode::default(),
+            params: FlockParams::default(),
+
+            geometry_capacity: 400,
+            sprite_capacity: 100,
+        }
+    }
+}
+
+impl AppOptions {
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let options: AppOptions = serde_json::from_str(json).map_err(|err| err.to_string())?;
+        options.validate()?;
+        Ok(options)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.canvas.is_empty() {
+            return Err("canvas must be a CSS selector, got an empty string".to_string());
+        }
+        if self.boid_textures.is_empty() {
+            return Err("boid_textures must list at least one image".to_string());
+        }
+        if self.geometry_capacity < MIN_GEOMETRY_CAPACITY {
+            return Err(format!(
+                "geometry_capacity must be at least {}, got {}",
+                MIN_GEOMETRY_CAPACITY, self.geometry_capacity
+            ));
+        }
+        if self.sprite_capacity == 0 {
+            return Err("sprite_capacity must hold at least one sprite, got 0".to_string());
+        }
+        self.params.validate()
+    }
+}
+
+// -----------------------------------------------------------------------------------------
+
+fn from_str<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr<Err = String>,
+{
+    let value = String::deserialize(deserializer)?;
+    value.parse().map_err(serde::de::Error::custom)
+}
@@ -0,0 +1,22 @@
+use crate::vector2::Vector2;
+
+// -----------------------------------------------------------------------------------------
+
+/// Point drawing in the boids within `radius` of it. `strength` weights the pull like
+/// the other `*_weight` params.
+#[derive(Debug, Clone, Copy)]
+pub struct Attractor {
+    pub position: Vector2<f32>,
+    pub strength: f32,
+    pub radius: f32,
+}
+
+impl Attractor {
+    pub fn new(position: Vector2<f32>, strength: f32, radius: f32) -> Self {
+        Self {
+            position,
+            strength,
+            radius,
+        }
+    }
+}
@@ -0,0 +1,182 @@
+//! Runs the flock without a browser and dumps every boid's state after each tick.
+//!
+//! ```text
+//! flock-sim [--boids N] [--ticks N] [--dt SECONDS] [--seed N] [--params FILE.json]
+//!           [--area SIZE] [--border THICK] [--boundary wrap|reflect|steer|open]
+//!           [--format csv|jsonl] [--output FILE]
+//! ```
+
+use std::error::Error;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::process;
+
+use flocking_simulation::boundary::BoundaryMode;
+use flocking_simulation::flock_params::FlockParams;
+use flocking_simulation::flocking::Flock;
+
+// -----------------------------------------------------------------------------------------
+
+const USAGE: &str = "usage: flock-sim [--boids N] [--ticks N] [--dt SECONDS] [--seed N] \
+[--params FILE.json] [--area SIZE] [--border THICK] [--boundary wrap|reflect|steer|open] \
+[--format csv|jsonl] [--output FILE]";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Format {
+    Csv,
+    JsonLines,
+}
+
+struct Options {
+    boids: usize,
+    ticks: usize,
+    delta_time: f32,
+    seed: u64,
+    params_file: Option<String>,
+    area_size: f32,
+    border_thick: f32,
+    boundary_mode: BoundaryMode,
+    format: Format,
+    output: Option<String>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            boids: 50,
+            ticks: 1000,
+            delta_time: 1.0 / 60.0,
+            seed: 0x5eed,
+            params_file: None,
+            area_size: 1000.0,
+            border_thick: 50.0,
+            boundary_mode: BoundaryMode::default(),
+            format: Format::Csv,
+            output: None,
+        }
+    }
+}
+
+// -----------------------------------------------------------------------------------------
+
+fn parse_args<I: Iterator<Item = String>>(mut args: I) -> Result<Options, String> {
+    fn value<T: std::str::FromStr>(flag: &str, value: Option<String>) -> Result<T, String> {
+        let value = value.ok_or_else(|| format!("missing value for {}", flag))?;
+        value
+            .parse()
+            .map_err(|_| format!("invalid value for {}: {}", flag, value))
+    }
+
+    let mut options = Options::default();
+
+    while let Some(flag) = args.next() {
+        match flag.as_str() {
+            "--boids" => options.boids = value(&flag, args.next())?,
+            "--ticks" => options.ticks = value(&flag, args.next())?,
+            "--dt" => options.delta_time = value(&flag, args.next())?,
+            "--seed" => options.seed = value(&flag, args.next())?,
+            "--params" => options.params_file = Some(value(&flag, args.next())?),
+            "--area" => options.area_size = value(&flag, args.next())?,
+            "--border" => options.border_thick = value(&flag, args.next())?,
+            "--boundary" => options.boundary_mode = value::<String>(&flag, args.next())?.parse()?,
+            "--output" => options.output = Some(value(&flag, args.next())?),
+            "--format" => {
+                options.format = match value::<String>(&flag, args.next())?.as_str() {
+                    "csv" => Format::Csv,
+                    "jsonl" => Format::JsonLines,
+                    other => return Err(format!("unknown format: {}", other)),
+                }
+            }
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                process::exit(0);
+            }
+            other => return Err(format!("unknown argument: {}", other)),
+        }
+    }
+
+    if !options.delta_time.is_finite() || options.delta_time <= 0.0 {
+        return Err("--dt must be positive".to_owned());
+    }
+
+    Ok(options)
+}
+
+fn load_params(path: &str) -> Result<FlockParams, Box<dyn Error>> {
+    let content = std::fs::read_to_string(path)?;
+    let params: FlockParams = serde_json::from_str(&content)?;
+    Ok(params)
+}
+
+// -----------------------------------------------------------------------------------------
+
+fn write_header(out: &mut dyn Write, format: Format) -> io::Result<()> {
+    match format {
+        Format::Csv => writeln!(out, "tick,boid,x,y,vx,vy"),
+        Format::JsonLines => Ok(()),
+    }
+}
+
+fn write_tick(out: &mut dyn Write, format: Format, tick: usize, flock: &Flock) -> io::Result<()> {
+    for boid in flock.boids() {
+        let (x, y) = (boid.position.x, boid.position.y);
+        let (vx, vy) = (boid.velocity.x, boid.velocity.y);
+
+        match format {
+            Format::Csv => writeln!(out, "{},{},{},{},{},{}", tick, boid.id, x, y, vx, vy)?,
+            Format::JsonLines => writeln!(
+                out,
+                r#"{{"tick":{},"boid":{},"x":{},"y":{},"vx":{},"vy":{}}}"#,
+                tick, boid.id, x, y, vx, vy
+            )?,
+        }
+    }
+
+    Ok(())
+}
+
+fn run(options: &Options) -> Result<(), Box<dyn Error>> {
+    let mut flock = Flock::new(options.boids, options.area_size, options.seed);
+
+    if let Some(path) = &options.params_file {
+        let params = load_params(path).map_err(|err| format!("{}: {}", path, err))?;
+        flock.set_params(params)?;
+    }
+    flock.set_boundary_mode(options.boundary_mode);
+
+    let mut out: Box<dyn Write> = match &options.output {
+        Some(path) => Box::new(BufWriter::new(File::create(path)?)),
+        None => Box::new(BufWriter::new(io::stdout())),
+    };
+
+    write_header(&mut out, options.format)?;
+    write_tick(&mut out, options.format, 0, &flock)?;
+
+    for tick in 1..=options.ticks {
+        flock.update(
+            options.delta_time,
+            options.area_size,
+            options.border_thick,
+            &[],
+        );
+        write_tick(&mut out, options.format, tick, &flock)?;
+    }
+
+    out.flush()?;
+    Ok(())
+}
+
+fn main() {
+    let options = match parse_args(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(err) => {
+            eprintln!("flock-sim: {}\n{}", err, USAGE);
+            process::exit(2);
+        }
+    };
+
+    if let Err(err) = run(&options) {
+        eprintln!("flock-sim: {}", err);
+        process::exit(1);
+    }
+}
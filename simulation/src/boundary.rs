@@ -0,0 +1,93 @@
+use std::str::FromStr;
+
+use crate::vector2::Vector2;
+
+// -----------------------------------------------------------------------------------------
+
+/// What happens to agents reaching the edge of the scene.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum BoundaryMode {
+    /// Agents leaving through one edge come back through the opposite one.
+    #[default]
+    Wrap,
+    /// Agents bounce off the walls.
+    Reflect,
+    /// Agents are pushed back by a force growing near the walls.
+    Steer,
+    /// Agents may leave the scene and are removed once they do.
+    Open,
+}
+
+impl FromStr for BoundaryMode {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "wrap" => Ok(BoundaryMode::Wrap),
+            "reflect" => Ok(BoundaryMode::Reflect),
+            "steer" => Ok(BoundaryMode::Steer),
+            "open" => Ok(BoundaryMode::Open),
+            other => Err(format!(
+                "unknown boundary mode `{}`, expected one of: wrap, reflect, steer, open",
+                other
+            )),
+        }
+    }
+}
+
+impl BoundaryMode {
+    /// Applies the mode to an agent which has just moved. Returns `false` when the agent
+    /// left the scene for good and should be removed.
+    pub fn confine(
+        self,
+        position: &mut Vector2<f32>,
+        velocity: &mut Vector2<f32>,
+        area_relative_size: f32,
+        border_thick: f32,
+    ) -> bool {
+        match self {
+            BoundaryMode::Wrap => {
+                wrap_around(position, area_relative_size, border_thick);
+                true
+            }
+            BoundaryMode::Reflect => {
+                let low = border_thick;
+                let high = area_relative_size - border_thick;
+                reflect(&mut position.x, &mut velocity.x, low, high);
+                reflect(&mut position.y, &mut velocity.y, low, high);
+                true
+            }
+            BoundaryMode::Steer => true,
+            BoundaryMode::Open => {
+                let inside = |value: f32| (0.0..=area_relative_size).contains(&value);
+                inside(position.x) && inside(position.y)
+            }
+        }
+    }
+}
+
+// -----------------------------------------------------------------------------------------
+
+fn wrap_around(position: &mut Vector2<f32>, area_relative_size: f32, border_thick: f32) {
+    if position.x > area_relative_size - border_thick / 2.0 {
+        position.x = border_thick / 2.0;
+    } else if position.x < border_thick / 2.0 {
+        position.x = area_relative_size - border_thick / 2.0;
+    }
+
+    if position.y > area_relative_size - border_thick / 2.0 {
+        position.y = border_thick / 2.0;
+    } else if position.y < border_thick / 2.0 {
+        position.y = area_relative_size - border_thick / 2.0;
+    }
+}
+
+fn reflect(position: &mut f32, velocity: &mut f32, low: f32, high: f32) {
+    if *position < low {
+        *position = (2.0 * low - *position).min(high);
+        *velocity = velocity.abs();
+    } else if *position > high {
+        *position = (2.0 * high - *position).max(low);
+        *velocity = -velocity.abs();
+    }
+}
@@ -0,0 +1,33 @@
+// -----------------------------------------------------------------------------------------
+
+/// RGBA color with channels in `[0, 1]`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Color {
+    pub red: f32,
+    pub green: f32,
+    pub blue: f32,
+    pub alpha: f32,
+}
+
+impl Color {
+    /// Color from `0xRRGGBBAA`.
+    pub fn from_hex(hex: u32) -> Self {
+        let channel = |shift: u32| ((hex >> shift) & 0xff) as f32 / 255.0;
+        Self {
+            red: channel(24),
+            green: channel(16),
+            blue: channel(8),
+            alpha: channel(0),
+        }
+    }
+
+    /// The color as `0xRRGGBBAA`, the inverse of `from_hex`.
+    pub fn to_hex(&self) -> u32 {
+        let channel =
+            |value: f32, shift: u32| ((value.clamp(0.0, 1.0) * 255.0).round() as u32) << shift;
+        channel(self.red, 24)
+            | channel(self.green, 16)
+            | channel(self.blue, 8)
+            | channel(self.alpha, 0)
+    }
+}
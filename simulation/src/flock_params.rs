@@ -0,0 +1,179 @@
+use serde::Deserialize;
+
+// -----------------------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FlockParams {
+    pub max_speed: f32,
+    pub max_force: f32,
+
+    pub neighborhood_zone: f32,
+    pub separation_zone: f32,
+    /// Field of view in degrees, 360 means no blind spot.
+    pub view_angle: f32,
+
+    pub cohesion_weight: f32,
+    pub separate_weight: f32,
+    pub cursor_separate_weight: f32,
+    pub align_weight: f32,
+
+    pub cursor_attract_weight: f32,
+    /// Distance from a target under which arriving boids start to slow down.
+    pub arrive_radius: f32,
+
+    pub avoid_weight: f32,
+    pub obstacle_look_ahead: f32,
+    pub obstacle_margin: f32,
+
+    pub predator_max_speed: f32,
+    pub predator_max_force: f32,
+    pub flee_radius: f32,
+    pub flee_weight: f32,
+
+    pub boundary_margin: f32,
+    pub boundary_weight: f32,
+
+    pub wander_weight: f32,
+    /// How far ahead of the boid the wander circle is.
+    pub wander_distance: f32,
+    pub wander_radius: f32,
+    /// Largest random turn of the wander target per tick, in degrees.
+    pub wander_jitter: f32,
+
+    pub follow_weight: f32,
+    pub evade_leader_weight: f32,
+    /// How far behind its leader a follower wants to be.
+    pub leader_behind_distance: f32,
+    /// Followers within `leader_sight_radius` of the point this far ahead of the leader
+    /// get out of its way.
+    pub leader_sight_distance: f32,
+    pub leader_sight_radius: f32,
+
+    pub path_weight: f32,
+    /// How far ahead boids predict their position when checking they are on the path,
+    /// and how far along the path they aim when they are not.
+    pub path_look_ahead: f32,
+
+    pub flow_weight: f32,
+}
+
+impl Default for FlockParams {
+    fn default() -> Self {
+        Self {
+            max_speed: 200.0,
+            max_force: 0.05,
+
+            neighborhood_zone: 360.0,
+            separation_zone: 65.0,
+            view_angle: 360.0,
+
+            cohesion_weight: 1.0,
+            separate_weight: 1.5,
+            cursor_separate_weight: 4.0,
+            align_weight: 1.0,
+
+            cursor_attract_weight: 2.0,
+            arrive_radius: 100.0,
+
+            avoid_weight: 10.0,
+            obstacle_look_ahead: 150.0,
+            obstacle_margin: 20.0,
+
+            predator_max_speed: 180.0,
+            predator_max_force: 0.08,
+            flee_radius: 150.0,
+            flee_weight: 6.0,
+
+            boundary_margin: 100.0,
+            boundary_weight: 10.0,
+
+            wander_weight: 1.0,
+            wander_distance: 80.0,
+            wander_radius: 40.0,
+            wander_jitter: 5.0,
+
+            follow_weight: 2.0,
+            evade_leader_weight: 3.0,
+            leader_behind_distance: 60.0,
+            leader_sight_distance: 80.0,
+            leader_sight_radius: 40.0,
+
+            path_weight: 3.0,
+            path_look_ahead: 50.0,
+
+            flow_weight: 1.0,
+        }
+    }
+}
+
+impl FlockParams {
+    pub fn validate(&self) -> Result<(), String> {
+        let positive = [
+            ("max_speed", self.max_speed),
+            ("max_force", self.max_force),
+            ("neighborhood_zone", self.neighborhood_zone),
+            ("separation_zone", self.separation_zone),
+            ("arrive_radius", self.arrive_radius),
+            ("obstacle_look_ahead", self.obstacle_look_ahead),
+            ("predator_max_speed", self.predator_max_speed),
+            ("predator_max_force", self.predator_max_force),
+            ("flee_radius", self.flee_radius),
+            ("boundary_margin", self.boundary_margin),
+        ];
+        for (name, value) in positive.iter() {
+            if !value.is_finite() || *value <= 0.0 {
+                return Err(format!("{} must be a positive number, got {}", name, value));
+            }
+        }
+
+        let weights = [
+            ("cohesion_weight", self.cohesion_weight),
+            ("separate_weight", self.separate_weight),
+            ("cursor_separate_weight", self.cursor_separate_weight),
+            ("align_weight", self.align_weight),
+            ("cursor_attract_weight", self.cursor_attract_weight),
+            ("avoid_weight", self.avoid_weight),
+            ("obstacle_margin", self.obstacle_margin),
+            ("flee_weight", self.flee_weight),
+            ("boundary_weight", self.boundary_weight),
+            ("wander_weight", self.wander_weight),
+            ("wander_distance", self.wander_distance),
+            ("wander_radius", self.wander_radius),
+            ("wander_jitter", self.wander_jitter),
+            ("follow_weight", self.follow_weight),
+            ("evade_leader_weight", self.evade_leader_weight),
+            ("leader_behind_distance", self.leader_behind_distance),
+            ("leader_sight_distance", self.leader_sight_distance),
+            ("leader_sight_radius", self.leader_sight_radius),
+            ("path_weight", self.path_weight),
+            ("path_look_ahead", self.path_look_ahead),
+            ("flow_weight", self.flow_weight),
+        ];
+        for (name, value) in weights.iter() {
+            if !value.is_finite() || *value < 0.0 {
+                return Err(format!(
+                    "{} must be a non-negative number, got {}",
+                    name, value
+                ));
+            }
+        }
+
+        if !self.view_angle.is_finite() || self.view_angle <= 0.0 || self.view_angle > 360.0 {
+            return Err(format!(
+                "view_angle must be in (0, 360] degrees, got {}",
+                self.view_angle
+            ));
+        }
+
+        // neighbours are looked up within the neighborhood zone only
+        if self.separation_zone > self.neighborhood_zone {
+            return Err(format!(
+                "separation_zone ({}) cannot be larger than neighborhood_zone ({})",
+                self.separation_zone, self.neighborhood_zone
+            ));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,1114 @@
+use crate::attractor::Attractor;
+use crate::boundary::BoundaryMode;
+use crate::flock_params::FlockParams;
+use crate::flow_field::FlowField;
+use crate::obstacle::Obstacle;
+use crate::path::Path;
+use crate::predator::Predator;
+use crate::rng::Rng;
+use crate::spatial_grid::SpatialGrid;
+use crate::species::{Interaction, InteractionMatrix, Species};
+use crate::vector2::Vector2;
+
+// -----------------------------------------------------------------------------------------
+
+// -----------------------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy)]
+pub struct Boid {
+    pub acceleration: Vector2<f32>,
+    pub velocity: Vector2<f32>,
+    pub position: Vector2<f32>,
+    /// Position before the last update, for drawing between two updates.
+    pub previous_position: Vector2<f32>,
+    /// Given by the flock, stays with the boid when others leave the scene.
+    pub id: usize,
+    pub species: usize,
+    pub leader: bool,
+    /// Where the wander target is on the wander circle, relative to the heading, in radians.
+    pub wander_angle: f32,
+    /// Jitters `wander_angle`. Each boid has its own, so a boid wanders the same whatever
+    /// its index in the flock.
+    wander_rng: Rng,
+}
+
+impl Boid {
+    pub fn new(position: &Vector2<f32>, rng: &mut Rng) -> Self {
+        let direction = rng.range(0.0, 360.0);
+        Self {
+            velocity: Vector2::from_angle(direction),
+            position: *position,
+            previous_position: *position,
+            id: 0,
+            species: 0,
+            leader: false,
+            wander_angle: 0.0,
+            wander_rng: Rng::new(rng.next_u64()),
+            acceleration: Vector2::zero(),
+        }
+    }
+
+    fn integrate(&mut self, step: &Step, params: &FlockParams) -> bool {
+        integrate(
+            &mut self.position,
+            &mut self.previous_position,
+            &mut self.velocity,
+            &mut self.acceleration,
+            params.max_speed,
+            step,
+        )
+    }
+
+    /// Position `alpha` of the way from the previous update to the last one.
+    pub fn interpolated_position(&self, alpha: f32) -> Vector2<f32> {
+        interpolate(&self.previous_position, &self.position, alpha)
+    }
+
+    /// Whether `point` lies in the boid's field of view: a cone of `view_angle` degrees
+    /// around its heading. The rest of the full angle is the blind spot behind the boid.
+    pub fn can_see(&self, point: &Vector2<f32>, view_angle: f32) -> bool {
+        if view_angle >= 360.0 || self.velocity.mag() <= 0.0 {
+            return true;
+        }
+
+        let pi = std::f32::consts::PI;
+        let direction = *point - self.position;
+        let difference =
+            (direction.heading() - self.velocity.heading() + pi).rem_euclid(2.0 * pi) - pi;
+        difference.abs() <= (view_angle / 2.0).to_radians()
+    }
+
+    pub fn seek(&self, target: &Vector2<f32>, params: &FlockParams) -> Vector2<f32> {
+        self.steer_to(*target - self.position, params)
+    }
+
+    /// Seeks a point on a circle ahead of the boid. The point drifts randomly around the
+    /// circle as `wander_angle` is jittered every tick.
+    pub fn wander(&self, params: &FlockParams) -> Vector2<f32> {
+        if self.velocity.mag() <= 0.0 {
+            return Vector2::zero();
+        }
+
+        let mut heading = self.velocity;
+        heading.normalize();
+
+        let angle = self.velocity.heading() + self.wander_angle;
+        let mut target = self.position;
+        target += heading * params.wander_distance;
+        target += Vector2::new(angle.cos(), angle.sin()) * params.wander_radius;
+        self.seek(&target, params)
+    }
+
+    /// Like `seek`, but the desired speed drops linearly to zero within `slowing_radius`
+    /// of the target, so the boid settles there instead of overshooting.
+    pub fn arrive(
+        &self,
+        target: &Vector2<f32>,
+        slowing_radius: f32,
+        params: &FlockParams,
+    ) -> Vector2<f32> {
+        let mut desired = *target - self.position;
+        let distance = desired.mag();
+        if distance >= slowing_radius {
+            return self.seek(target, params);
+        }
+
+        if distance > 0.0 {
+            desired.normalize();
+            desired *= params.max_speed * distance / slowing_radius;
+        }
+        let mut steer = desired - self.velocity;
+        steer.limit(params.max_force);
+        steer
+    }
+
+    /// The wander jitter is seeded from `position` and `velocity`.
+    pub fn with_velocity(position: &Vector2<f32>, velocity: &Vector2<f32>) -> Self {
+        let bits = |v: &Vector2<f32>| (v.x.to_bits() as u64) << 32 | v.y.to_bits() as u64;
+        Self {
+            velocity: *velocity,
+            position: *position,
+            previous_position: *position,
+            id: 0,
+            species: 0,
+            leader: false,
+            wander_angle: 0.0,
+            wander_rng: Rng::new(bits(position) ^ bits(velocity).rotate_left(17)),
+            acceleration: Vector2::zero(),
+        }
+    }
+}
+
+// -----------------------------------------------------------------------------------------
+
+/// How `Flock` finds the candidates checked by the neighbour rules.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NeighbourSearch {
+    /// Every boid is a candidate for every other boid.
+    BruteForce,
+    /// Only boids from grid cells around the current one are candidates.
+    Grid,
+}
+
+/// Order in which `Flock::update` applies the new state of the boids.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UpdateMode {
+    /// Boids are moved in place, so later boids see positions already updated this tick.
+    /// The default.
+    Sequential,
+    /// Every boid reads the previous tick and writes into a back buffer, swapped at the end.
+    Synchronous,
+}
+
+/// What a cursor does to the boids around it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CursorMode {
+    /// Boids within the separation zone are pushed away from the cursor.
+    Repel,
+    /// Every boid arrives at the cursor.
+    Attract,
+}
+
+/// Mouse pointer or touch point acting on the flock. Leaders are always attracted.
+#[derive(Debug, Clone, Copy)]
+pub struct Cursor {
+    pub position: Vector2<f32>,
+    pub mode: CursorMode,
+}
+
+impl Cursor {
+    pub fn repel(position: Vector2<f32>) -> Self {
+        Self {
+            position,
+            mode: CursorMode::Repel,
+        }
+    }
+
+    pub fn attract(position: Vector2<f32>) -> Self {
+        Self {
+            position,
+            mode: CursorMode::Attract,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Flock {
+    boids: Vec<Boid>,
+    back_buffer: Vec<Boid>,
+    grid: SpatialGrid,
+    neighbour_search: NeighbourSearch,
+    update_mode: UpdateMode,
+    params: FlockParams,
+    species: Vec<Species>,
+    interactions: InteractionMatrix,
+    obstacles: Vec<Obstacle>,
+    attractors: Vec<Attractor>,
+    path: Option<Path>,
+    flow_field: Option<FlowField>,
+    /// Indices of the boids marked as leaders.
+    leaders: Vec<usize>,
+    predators: Vec<Predator>,
+    boundary_mode: BoundaryMode,
+    area_relative_size: f32,
+    border_thick: f32,
+    /// Id of the next spawned boid.
+    next_id: usize,
+    rng: Rng,
+}
+
+impl Flock {
+    pub fn new(count: usize, area_relative_size: f32, seed: u64) -> Self {
+        let mut flock = Flock::from_boids(vec![], area_relative_size);
+        flock.rng = Rng::new(seed);
+        // the default species always exists
+        flock.spawn_species(count, 0);
+        flock
+    }
+
+    /// Every species used by `boids` gets the default look. Boids of different species
+    /// only avoid each other until `set_interaction` says otherwise. The boids are given
+    /// ids in order, starting at 0.
+    pub fn from_boids(mut boids: Vec<Boid>, area_relative_size: f32) -> Self {
+        for (id, boid) in boids.iter_mut().enumerate() {
+            boid.id = id;
+        }
+        let next_id = boids.len();
+
+        let params = FlockParams::default();
+        let species_count = boids.iter().map(|boid| boid.species + 1).max().unwrap_or(1);
+        let mut flock = Flock {
+            back_buffer: Vec::with_capacity(boids.len()),
+            boids,
+            grid: SpatialGrid::new(area_relative_size, params.neighborhood_zone),
+            neighbour_search: NeighbourSearch::Grid,
+            update_mode: UpdateMode::Sequential,
+            params,
+            species: vec![Species::new(0, None); species_count],
+            interactions: InteractionMatrix::new(species_count),
+            obstacles: vec![],
+            attractors: vec![],
+            path: None,
+            flow_field: None,
+            leaders: vec![],
+            predators: vec![],
+            boundary_mode: BoundaryMode::default(),
+            area_relative_size,
+            border_thick: 0.0,
+            next_id,
+            rng: Rng::new(0),
+        };
+        flock.refresh_leaders();
+        flock
+    }
+
+    pub fn boids(&self) -> &[Boid] {
+        &self.boids
+    }
+
+    pub fn params(&self) -> &FlockParams {
+        &self.params
+    }
+
+    pub fn set_params(&mut self, params: FlockParams) -> Result<(), String> {
+        params.validate()?;
+        self.grid.set_cell_size(params.neighborhood_zone);
+        self.params = params;
+        Ok(())
+    }
+
+    pub fn species(&self) -> &[Species] {
+        &self.species
+    }
+
+    /// Registers a new species and returns its id.
+    pub fn add_species(&mut self, species: Species) -> usize {
+        self.species.push(species);
+        self.interactions.resize(self.species.len());
+        self.species.len() - 1
+    }
+
+    /// Adds `count` boids of the given species in the middle of the scene.
+    pub fn spawn(&mut self, count: usize, species_id: usize) -> Result<(), String> {
+        if species_id >= self.species.len() {
+            return Err(format!("unknown species {}", species_id));
+        }
+        self.spawn_species(count, species_id);
+        Ok(())
+    }
+
+    fn spawn_species(&mut self, count: usize, species_id: usize) {
+        let position = Vector2::new(self.area_relative_size / 2.0, self.area_relative_size / 2.0);
+        for _ in 0..count {
+            let mut boid = Boid::new(&position, &mut self.rng);
+            boid.id = self.next_id;
+            boid.species = species_id;
+            self.next_id += 1;
+            self.boids.push(boid);
+        }
+    }
+
+    /// Replaces the boids with `count` new ones of the default species, spawned as by
+    /// `Flock::new` with `seed`. Everything else, from species to predators, is kept.
+    pub fn respawn(&mut self, count: usize, seed: u64) {
+        self.boids.clear();
+        self.leaders.clear();
+        self.next_id = 0;
+        self.rng = Rng::new(seed);
+        self.spawn_species(count, 0);
+    }
+
+    pub fn interactions(&self) -> &InteractionMatrix {
+        &self.interactions
+    }
+
+    /// Sets how boids of the `observer` species treat neighbours of the `other` species.
+    pub fn set_interaction(
+        &mut self,
+        observer: usize,
+        other: usize,
+        interaction: Interaction,
+    ) -> Result<(), String> {
+        for &species_id in [observer, other].iter() {
+            if species_id >= self.species.len() {
+                return Err(format!("unknown species {}", species_id));
+            }
+        }
+
+        self.interactions.set(observer, other, interaction);
+        Ok(())
+    }
+
+    pub fn obstacles(&self) -> &[Obstacle] {
+        &self.obstacles
+    }
+
+    pub fn add_obstacle(&mut self, obstacle: Obstacle) {
+        self.obstacles.push(obstacle);
+    }
+
+    pub fn clear_obstacles(&mut self) {
+        self.obstacles.clear();
+    }
+
+    pub fn leaders(&self) -> &[usize] {
+        &self.leaders
+    }
+
+    /// Promotes the boid to a leader, or demotes it back to a follower.
+    pub fn set_leader(&mut self, boid_index: usize, leader: bool) -> Result<(), String> {
+        let boid = self
+            .boids
+            .get_mut(boid_index)
+            .ok_or_else(|| format!("unknown boid {}", boid_index))?;
+        boid.leader = leader;
+        self.refresh_leaders();
+        Ok(())
+    }
+
+    fn refresh_leaders(&mut self) {
+        self.leaders.clear();
+        for (index, boid) in self.boids.iter().enumerate() {
+            if boid.leader {
+                self.leaders.push(index);
+            }
+        }
+    }
+
+    pub fn attractors(&self) -> &[Attractor] {
+        &self.attractors
+    }
+
+    pub fn add_attractor(&mut self, attractor: Attractor) {
+        self.attractors.push(attractor);
+    }
+
+    pub fn clear_attractors(&mut self) {
+        self.attractors.clear();
+    }
+
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_ref()
+    }
+
+    pub fn set_path(&mut self, path: Option<Path>) {
+        self.path = path;
+    }
+
+    pub fn flow_field(&self) -> Option<&FlowField> {
+        self.flow_field.as_ref()
+    }
+
+    pub fn set_flow_field(&mut self, flow_field: Option<FlowField>) {
+        self.flow_field = flow_field;
+    }
+
+    pub fn predators(&self) -> &[Predator] {
+        &self.predators
+    }
+
+    pub fn add_predator(&mut self, predator: Predator) {
+        self.predators.push(predator);
+    }
+
+    /// Removes the newest predators or spawns new ones at random places of the scene.
+    pub fn set_predator_count(&mut self, count: usize) {
+        self.predators.truncate(count);
+        while self.predators.len() < count {
+            let position = Vector2::new(
+                self.rng.range(0.0, self.area_relative_size),
+                self.rng.range(0.0, self.area_relative_size),
+            );
+            let predator = Predator::new(&position, &mut self.rng);
+            self.predators.push(predator);
+        }
+    }
+
+    pub fn boundary_mode(&self) -> BoundaryMode {
+        self.boundary_mode
+    }
+
+    pub fn set_boundary_mode(&mut self, boundary_mode: BoundaryMode) {
+        self.boundary_mode = boundary_mode;
+    }
+
+    pub fn set_neighbour_search(&mut self, neighbour_search: NeighbourSearch) {
+        self.neighbour_search = neighbour_search;
+    }
+
+    pub fn set_update_mode(&mut self, update_mode: UpdateMode) {
+        self.update_mode = update_mode;
+    }
+
+    fn find_neighbours(&self, current_boid_index: usize, result: &mut Vec<usize>) {
+        match self.neighbour_search {
+            NeighbourSearch::BruteForce => {
+                result.clear();
+                result.extend(0..self.boids.len());
+            }
+            NeighbourSearch::Grid => {
+                let position = &self.boids[current_boid_index].position;
+                self.grid
+                    .query(position, self.params.neighborhood_zone, result);
+            }
+        }
+    }
+
+    pub fn update(
+        &mut self,
+        delta_time: f32,
+        area_relative_size: f32,
+        border_thick: f32,
+        cursors: &[Cursor],
+    ) {
+        self.area_relative_size = area_relative_size;
+        self.border_thick = border_thick;
+        let step = Step {
+            delta_time,
+            area_relative_size,
+            border_thick,
+            boundary_mode: self.boundary_mode,
+        };
+
+        // predators pick their prey before the flock moves
+        for predator_index in 0..self.predators.len() {
+            let mut hunt_force = self.hunt(predator_index);
+            if self.boundary_mode == BoundaryMode::Steer {
+                let predator = &self.predators[predator_index];
+                hunt_force += self.containment(
+                    &predator.position,
+                    &predator.velocity,
+                    self.params.predator_max_speed,
+                    self.params.predator_max_force,
+                ) * self.params.boundary_weight;
+            }
+            self.predators[predator_index].acceleration += hunt_force;
+        }
+
+        // drawn in one pass, so the rules below stay free of the rng
+        if self.params.wander_weight != 0.0 {
+            let jitter = self.params.wander_jitter.to_radians();
+            for boid in self.boids.iter_mut() {
+                boid.wander_angle += boid.wander_rng.range(-jitter, jitter);
+            }
+        }
+
+        if self.neighbour_search == NeighbourSearch::Grid {
+            self.grid.rebuild(
+                area_relative_size,
+                self.boids.iter().map(|boid| &boid.position),
+            );
+        }
+
+        match self.update_mode {
+            UpdateMode::Sequential => self.update_sequential(&step, cursors),
+            UpdateMode::Synchronous => self.update_synchronous(&step, cursors),
+        }
+
+        let mut inside = Vec::with_capacity(self.predators.len());
+        for predator in self.predators.iter_mut() {
+            inside.push(predator.integrate(&step, &self.params));
+        }
+        retain_inside(&mut self.predators, &inside);
+
+        // boids which left an open scene shift the indices
+        self.refresh_leaders();
+    }
+
+    fn update_sequential(&mut self, step: &Step, cursors: &[Cursor]) {
+        let mut neighbours = vec![];
+        let mut inside = Vec::with_capacity(self.boids.len());
+
+        for boid_index in 0..self.boids.len() {
+            // flock
+            self.find_neighbours(boid_index, &mut neighbours);
+            let flock_force = self.flock(boid_index, &neighbours, cursors);
+            self.boids[boid_index].acceleration += flock_force;
+
+            let current_boid = &mut self.boids[boid_index];
+            let old_position = current_boid.position;
+            inside.push(current_boid.integrate(step, &self.params));
+
+            // later boids of this tick must see the new position
+            if self.neighbour_search == NeighbourSearch::Grid {
+                let new_position = current_boid.position;
+                self.grid.relocate(boid_index, &old_position, &new_position);
+            }
+        }
+
+        // boids which left an open scene are removed only now, not to shift indices above
+        retain_inside(&mut self.boids, &inside);
+    }
+
+    fn update_synchronous(&mut self, step: &Step, cursors: &[Cursor]) {
+        let mut neighbours = vec![];
+        let mut back_buffer = std::mem::take(&mut self.back_buffer);
+        back_buffer.clear();
+
+        for boid_index in 0..self.boids.len() {
+            // flock
+            self.find_neighbours(boid_index, &mut neighbours);
+            let mut next_boid = self.boids[boid_index];
+            next_boid.acceleration += self.flock(boid_index, &neighbours, cursors);
+
+            let inside = next_boid.integrate(step, &self.params);
+            if inside {
+                back_buffer.push(next_boid);
+            }
+        }
+
+        self.back_buffer = std::mem::replace(&mut self.boids, back_buffer);
+    }
+}
+
+/// What moving a boid or a predator by one update depends on, besides the agent itself.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Step {
+    pub delta_time: f32,
+    pub area_relative_size: f32,
+    pub border_thick: f32,
+    pub boundary_mode: BoundaryMode,
+}
+
+/// Applies the acceleration of an agent, moves it and keeps it in the scene. Returns
+/// whether the agent is still inside.
+pub(crate) fn integrate(
+    position: &mut Vector2<f32>,
+    previous_position: &mut Vector2<f32>,
+    velocity: &mut Vector2<f32>,
+    acceleration: &mut Vector2<f32>,
+    max_speed: f32,
+    step: &Step,
+) -> bool {
+    // update
+    *previous_position = *position;
+    *velocity += *acceleration;
+    velocity.limit(max_speed);
+    *position += *velocity * step.delta_time;
+    *acceleration *= 0.0;
+
+    // borders
+    let moved_to = *position;
+    let inside = step.boundary_mode.confine(
+        position,
+        velocity,
+        step.area_relative_size,
+        step.border_thick,
+    );
+    if !same_point(&moved_to, position) {
+        // do not draw the agent sliding across the scene after a wrap
+        *previous_position = *position;
+    }
+    inside
+}
+
+pub(crate) fn same_point(a: &Vector2<f32>, b: &Vector2<f32>) -> bool {
+    a.x == b.x && a.y == b.y
+}
+
+pub(crate) fn interpolate(
+    previous: &Vector2<f32>,
+    current: &Vector2<f32>,
+    alpha: f32,
+) -> Vector2<f32> {
+    let mut position = *previous;
+    position += (*current - *previous) * alpha;
+    position
+}
+
+fn retain_inside<T>(agents: &mut Vec<T>, inside: &[bool]) {
+    let mut index = 0;
+    agents.retain(|_| {
+        index += 1;
+        inside[index - 1]
+    });
+}
+
+// -----------------------------------------------------------------------------------------
+
+/// Everything the neighbour rules need, gathered in a single pass over the neighbours.
+struct NeighbourSums {
+    position_sum: Vector2<f32>,
+    cohesion_count: usize,
+    velocity_sum: Vector2<f32>,
+    alignment_count: usize,
+    separation_sum: Vector2<f32>,
+    too_close_count: usize,
+}
+
+impl Boid {
+    fn steer_to(&self, mut desired: Vector2<f32>, params: &FlockParams) -> Vector2<f32> {
+        desired.normalize();
+        desired *= params.max_speed;
+        let mut steer = desired - self.velocity;
+        steer.limit(params.max_force);
+        steer
+    }
+
+    /// Steers out of the way of something along `heading`, a unit vector. Only the part of
+    /// `away` across the heading is kept, so the boid moves aside instead of braking; when
+    /// there is none it turns left.
+    fn steer_aside(
+        &self,
+        away: Vector2<f32>,
+        heading: Vector2<f32>,
+        params: &FlockParams,
+    ) -> Vector2<f32> {
+        let along = away.x * heading.x + away.y * heading.y;
+        let mut lateral = away - heading * along;
+        if lateral.mag() <= f32::EPSILON {
+            lateral = Vector2::new(-heading.y, heading.x);
+        }
+        self.steer_to(lateral, params)
+    }
+
+    fn cohesion_force(&self, sums: &NeighbourSums, params: &FlockParams) -> Vector2<f32> {
+        if sums.cohesion_count == 0 {
+            return Vector2::zero();
+        }
+
+        let mut center_of_mas = sums.position_sum;
+        center_of_mas /= sums.cohesion_count as f32;
+        self.seek(&center_of_mas, params)
+    }
+
+    fn separation_force(&self, sums: &NeighbourSums, params: &FlockParams) -> Vector2<f32> {
+        let mut steer_vector = sums.separation_sum;
+
+        if sums.too_close_count > 0 {
+            steer_vector /= sums.too_close_count as f32;
+        }
+
+        if steer_vector.mag() > 0.0 {
+            steer_vector = self.steer_to(steer_vector, params);
+        }
+
+        steer_vector
+    }
+
+    fn alignment_force(&self, sums: &NeighbourSums, params: &FlockParams) -> Vector2<f32> {
+        if sums.alignment_count == 0 {
+            return Vector2::zero();
+        }
+
+        let mut average_velocity = sums.velocity_sum;
+        average_velocity /= sums.alignment_count as f32;
+        self.steer_to(average_velocity, params)
+    }
+}
+
+impl Flock {
+    fn neighbour_sums(&self, current_boid_index: usize, neighbours: &[usize]) -> NeighbourSums {
+        let current_boid = &self.boids[current_boid_index];
+
+        let mut sums = NeighbourSums {
+            position_sum: Vector2::zero(),
+            cohesion_count: 0,
+            velocity_sum: Vector2::zero(),
+            alignment_count: 0,
+            separation_sum: Vector2::zero(),
+            too_close_count: 0,
+        };
+
+        for &index in neighbours {
+            if index == current_boid_index {
+                continue;
+            }
+
+            let boid = &self.boids[index];
+            let interaction = self.interactions.get(current_boid.species, boid.species);
+            if interaction.ignores() {
+                continue;
+            }
+
+            let distance = boid.position.distance_to(&current_boid.position);
+            if distance <= 0.0 || distance >= self.params.neighborhood_zone {
+                continue;
+            }
+
+            if !current_boid.can_see(&boid.position, self.params.view_angle) {
+                continue;
+            }
+
+            if interaction.cohere {
+                sums.position_sum += boid.position;
+                sums.cohesion_count += 1;
+            }
+
+            if interaction.align {
+                sums.velocity_sum += boid.velocity;
+                sums.alignment_count += 1;
+            }
+
+            if interaction.avoid && distance < self.params.separation_zone {
+                let mut diff = current_boid.position - boid.position;
+                diff.normalize();
+                diff /= distance;
+                sums.separation_sum += diff;
+                sums.too_close_count += 1;
+            }
+        }
+
+        sums
+    }
+
+    pub fn cohesion(&self, current_boid_index: usize, neighbours: &[usize]) -> Vector2<f32> {
+        let sums = self.neighbour_sums(current_boid_index, neighbours);
+        self.boids[current_boid_index].cohesion_force(&sums, &self.params)
+    }
+
+    pub fn separate(&self, current_boid_index: usize, neighbours: &[usize]) -> Vector2<f32> {
+        let sums = self.neighbour_sums(current_boid_index, neighbours);
+        self.boids[current_boid_index].separation_force(&sums, &self.params)
+    }
+
+    pub fn separate_from_cursor(
+        &self,
+        current_boid_index: usize,
+        mouse_cursor: &Vector2<f32>,
+    ) -> Vector2<f32> {
+        let current_boid = &self.boids[current_boid_index];
+        let mut steer_vector = Vector2::zero();
+        let distance = current_boid.position.distance_to(mouse_cursor);
+
+        if distance < self.params.separation_zone && distance > 0.0 {
+            let mut diff = current_boid.position - *mouse_cursor;
+            diff.normalize();
+            diff /= distance;
+            steer_vector += diff;
+
+            if steer_vector.mag() > 0.0 {
+                steer_vector = current_boid.steer_to(steer_vector, &self.params);
+            }
+        }
+
+        steer_vector
+    }
+
+    pub fn attract_to_cursor(
+        &self,
+        current_boid_index: usize,
+        mouse_cursor: &Vector2<f32>,
+    ) -> Vector2<f32> {
+        let current_boid = &self.boids[current_boid_index];
+        current_boid.arrive(mouse_cursor, self.params.arrive_radius, &self.params)
+    }
+
+    /// Closest leader within the neighbourhood zone, other than the boid itself.
+    pub fn closest_leader(&self, current_boid_index: usize) -> Option<&Boid> {
+        let current_boid = &self.boids[current_boid_index];
+
+        self.leaders
+            .iter()
+            .filter(|&&index| index != current_boid_index)
+            .map(|&index| &self.boids[index])
+            .map(|leader| (leader, leader.position.distance_to(&current_boid.position)))
+            .filter(|(_, distance)| *distance < self.params.neighborhood_zone)
+            .min_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal))
+            .map(|(leader, _)| leader)
+    }
+
+    /// Arrives at a point `leader_behind_distance` behind `leader`.
+    pub fn follow_leader(&self, current_boid_index: usize, leader: &Boid) -> Vector2<f32> {
+        let current_boid = &self.boids[current_boid_index];
+
+        let mut behind = leader.position;
+        if leader.velocity.mag() > 0.0 {
+            let mut heading = leader.velocity;
+            heading.normalize();
+            behind -= heading * self.params.leader_behind_distance;
+        }
+
+        current_boid.arrive(&behind, self.params.arrive_radius, &self.params)
+    }
+
+    /// Steers sideways when the boid is in front of `leader`, within `leader_sight_radius`
+    /// of the leader or of the point `leader_sight_distance` ahead.
+    pub fn evade_leader(&self, current_boid_index: usize, leader: &Boid) -> Vector2<f32> {
+        let current_boid = &self.boids[current_boid_index];
+
+        if leader.velocity.mag() <= 0.0 {
+            return Vector2::zero();
+        }
+
+        let mut heading = leader.velocity;
+        heading.normalize();
+        let mut ahead = leader.position;
+        ahead += heading * self.params.leader_sight_distance;
+
+        let radius = self.params.leader_sight_radius;
+        let in_the_way = current_boid.position.distance_to(&ahead) < radius
+            || current_boid.position.distance_to(&leader.position) < radius;
+        if !in_the_way {
+            return Vector2::zero();
+        }
+
+        // move off the leader's path rather than away from it
+        let away = current_boid.position - leader.position;
+        current_boid.steer_aside(away, heading, &self.params)
+    }
+
+    /// Predicts where the boid will be `path_look_ahead` from now and, if that is outside
+    /// the path corridor, seeks a point further along the closest path segment.
+    pub fn follow_path(&self, current_boid_index: usize) -> Vector2<f32> {
+        let current_boid = &self.boids[current_boid_index];
+        let path = match &self.path {
+            Some(path) => path,
+            None => return Vector2::zero(),
+        };
+
+        let mut future = current_boid.position;
+        if current_boid.velocity.mag() > 0.0 {
+            let mut heading = current_boid.velocity;
+            heading.normalize();
+            future += heading * self.params.path_look_ahead;
+        }
+
+        let (normal_point, direction) = match path.project(&future) {
+            Some(projection) => projection,
+            None => return Vector2::zero(),
+        };
+        if future.distance_to(&normal_point) <= path.radius {
+            return Vector2::zero();
+        }
+
+        let mut target = normal_point;
+        target += direction * self.params.path_look_ahead;
+        current_boid.seek(&target, &self.params)
+    }
+
+    /// Push of the flow field at the boid position. Unlike the steering forces it is not
+    /// limited by `max_force`, the current carries the boid whatever it wants.
+    pub fn current(&self, current_boid_index: usize) -> Vector2<f32> {
+        match &self.flow_field {
+            Some(flow_field) => flow_field.sample(&self.boids[current_boid_index].position),
+            None => Vector2::zero(),
+        }
+    }
+
+    /// Sum of the pulls of the attractors the boid is within the radius of, each already
+    /// weighted by the attractor strength.
+    pub fn attract(&self, current_boid_index: usize) -> Vector2<f32> {
+        let current_boid = &self.boids[current_boid_index];
+        let mut steer_vector = Vector2::zero();
+
+        for attractor in self.attractors.iter() {
+            let distance = current_boid.position.distance_to(&attractor.position);
+            if distance < attractor.radius {
+                steer_vector += current_boid.arrive(
+                    &attractor.position,
+                    self.params.arrive_radius,
+                    &self.params,
+                ) * attractor.strength;
+            }
+        }
+
+        steer_vector
+    }
+
+    /// Looks ahead along the velocity and steers sideways from the nearest obstacle
+    /// the boid is about to hit.
+    pub fn avoid_obstacles(&self, current_boid_index: usize) -> Vector2<f32> {
+        let current_boid = &self.boids[current_boid_index];
+        let params = &self.params;
+
+        if self.obstacles.is_empty() || current_boid.velocity.mag() <= 0.0 {
+            return Vector2::zero();
+        }
+
+        let mut heading = current_boid.velocity;
+        heading.normalize();
+
+        let mut probes = [current_boid.position; 3];
+        probes[1] += heading * (params.obstacle_look_ahead / 2.0);
+        probes[2] += heading * params.obstacle_look_ahead;
+
+        let mut threat: Option<(f32, Vector2<f32>)> = None;
+
+        for obstacle in self.obstacles.iter() {
+            let probe = probes
+                .iter()
+                .find(|probe| obstacle.distance_to(probe) < params.obstacle_margin);
+
+            if let Some(probe) = probe {
+                let distance = obstacle.distance_to(&current_boid.position);
+                let closer = match threat {
+                    Some((closest, _)) => distance < closest,
+                    None => true,
+                };
+                if closer {
+                    threat = Some((distance, obstacle.away_from(probe)));
+                }
+            }
+        }
+
+        let away = match threat {
+            Some((_, away)) => away,
+            None => return Vector2::zero(),
+        };
+
+        current_boid.steer_aside(away, heading, params)
+    }
+
+    /// Pushes an agent back towards the scene when it gets within `boundary_margin` of
+    /// the walls. The force keeps growing past the walls.
+    pub fn containment(
+        &self,
+        position: &Vector2<f32>,
+        velocity: &Vector2<f32>,
+        max_speed: f32,
+        max_force: f32,
+    ) -> Vector2<f32> {
+        let margin = self.params.boundary_margin;
+        let low = self.border_thick;
+        let high = self.area_relative_size - self.border_thick;
+        let proximity = |distance: f32| ((margin - distance) / margin).max(0.0);
+
+        let push = Vector2::new(
+            proximity(position.x - low) - proximity(high - position.x),
+            proximity(position.y - low) - proximity(high - position.y),
+        );
+        let strength = push.mag();
+        if strength <= 0.0 {
+            return Vector2::zero();
+        }
+
+        // keep the speed along the wall, turn away from it
+        let mut desired = *velocity;
+        if push.x != 0.0 {
+            desired.x = push.x.signum() * max_speed;
+        }
+        if push.y != 0.0 {
+            desired.y = push.y.signum() * max_speed;
+        }
+
+        let mut steer = desired - *velocity;
+        steer.limit(max_force);
+        steer * strength
+    }
+
+    /// Like `separate_from_cursor`, but for every predator within the flee radius.
+    pub fn flee_predators(&self, current_boid_index: usize) -> Vector2<f32> {
+        let current_boid = &self.boids[current_boid_index];
+
+        let mut steer_vector = Vector2::zero();
+        let mut predators_count = 0;
+
+        for predator in self.predators.iter() {
+            let distance = current_boid.position.distance_to(&predator.position);
+            if distance < self.params.flee_radius && distance > 0.0 {
+                let mut diff = current_boid.position - predator.position;
+                diff.normalize();
+                diff /= distance;
+                steer_vector += diff;
+                predators_count += 1;
+            }
+        }
+
+        if predators_count > 0 {
+            steer_vector /= predators_count as f32;
+        }
+
+        if steer_vector.mag() > 0.0 {
+            steer_vector = current_boid.steer_to(steer_vector, &self.params);
+        }
+
+        steer_vector
+    }
+
+    /// Steers a predator towards the closest boid.
+    pub fn hunt(&self, predator_index: usize) -> Vector2<f32> {
+        let predator = &self.predators[predator_index];
+
+        let prey = self.boids.iter().min_by(|a, b| {
+            let distance_a = a.position.distance_to(&predator.position);
+            let distance_b = b.position.distance_to(&predator.position);
+            distance_a
+                .partial_cmp(&distance_b)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        match prey {
+            Some(prey) => predator.pursue(&prey.position, &self.params),
+            None => Vector2::zero(),
+        }
+    }
+
+    pub fn align(&self, current_boid_index: usize, neighbours: &[usize]) -> Vector2<f32> {
+        let sums = self.neighbour_sums(current_boid_index, neighbours);
+        self.boids[current_boid_index].alignment_force(&sums, &self.params)
+    }
+
+    pub fn flock(
+        &self,
+        current_boid_index: usize,
+        neighbours: &[usize],
+        cursors: &[Cursor],
+    ) -> Vector2<f32> {
+        let current_boid = &self.boids[current_boid_index];
+        let sums = self.neighbour_sums(current_boid_index, neighbours);
+
+        let params = &self.params;
+
+        // boids with a leader in their neighbourhood trail behind it instead of flocking
+        let leader = if current_boid.leader {
+            None
+        } else {
+            self.closest_leader(current_boid_index)
+        };
+        let following = leader.is_some();
+
+        let mut result = Vector2::zero();
+        result += current_boid.separation_force(&sums, params) * params.separate_weight;
+        if let Some(leader) = leader {
+            result += self.follow_leader(current_boid_index, leader) * params.follow_weight;
+            result += self.evade_leader(current_boid_index, leader) * params.evade_leader_weight;
+        } else if !current_boid.leader {
+            // leaders go their own way
+            result += current_boid.cohesion_force(&sums, params) * params.cohesion_weight;
+            result += current_boid.alignment_force(&sums, params) * params.align_weight;
+        }
+        for cursor in cursors {
+            result += match cursor.mode {
+                _ if current_boid.leader => {
+                    self.attract_to_cursor(current_boid_index, &cursor.position)
+                        * params.cursor_attract_weight
+                }
+                CursorMode::Repel => {
+                    self.separate_from_cursor(current_boid_index, &cursor.position)
+                        * params.cursor_separate_weight
+                }
+                CursorMode::Attract => {
+                    self.attract_to_cursor(current_boid_index, &cursor.position)
+                        * params.cursor_attract_weight
+                }
+            };
+        }
+        result += self.attract(current_boid_index);
+        // followers leave the patrol to their leader
+        if !following {
+            result += self.follow_path(current_boid_index) * params.path_weight;
+        }
+        let idle = if current_boid.leader {
+            cursors.is_empty() && self.path.is_none()
+        } else {
+            // nobody to flock with
+            !following && sums.cohesion_count == 0 && sums.alignment_count == 0
+        };
+        if idle {
+            result += current_boid.wander(params) * params.wander_weight;
+        }
+        result += self.current(current_boid_index) * params.flow_weight;
+        result += self.avoid_obstacles(current_boid_index) * params.avoid_weight;
+        result += self.flee_predators(current_boid_index) * params.flee_weight;
+        if self.boundary_mode == BoundaryMode::Steer {
+            result += self.containment(
+                &current_boid.position,
+                &current_boid.velocity,
+                params.max_speed,
+                params.max_force,
+            ) * params.boundary_weight;
+        }
+        result
+    }
+}
@@ -0,0 +1,160 @@
+use crate::rng::Rng;
+use crate::spatial_grid::{cell_coord, column_count};
+use crate::vector2::Vector2;
+
+// -----------------------------------------------------------------------------------------
+
+/// Grid of force vectors over the square scene `[0, area_size]`, pushing the boids like
+/// a current or wind. Positions outside the scene use the closest border cell.
+#[derive(Debug, Clone)]
+pub struct FlowField {
+    cell_size: f32,
+    columns: usize,
+    vectors: Vec<Vector2<f32>>,
+}
+
+impl FlowField {
+    /// Field of zero vectors, to be filled with `set`.
+    pub fn new(area_size: f32, cell_size: f32) -> Self {
+        let columns = column_count(area_size, cell_size);
+        Self {
+            cell_size,
+            columns,
+            vectors: vec![Vector2::zero(); columns * columns],
+        }
+    }
+
+    /// Samples `field` at the center of every cell.
+    pub fn from_fn<F>(area_size: f32, cell_size: f32, field: F) -> Self
+    where
+        F: Fn(Vector2<f32>) -> Vector2<f32>,
+    {
+        let mut flow_field = FlowField::new(area_size, cell_size);
+        for row in 0..flow_field.columns {
+            for column in 0..flow_field.columns {
+                let vector = field(flow_field.cell_center(column, row));
+                flow_field.set(column, row, vector);
+            }
+        }
+        flow_field
+    }
+
+    /// Swirling field of vectors of length `strength`, pointing along the angle given by
+    /// Perlin noise. `noise_scale` is the size of the noise features in scene units.
+    pub fn noise(
+        area_size: f32,
+        cell_size: f32,
+        noise_scale: f32,
+        strength: f32,
+        rng: &mut Rng,
+    ) -> Self {
+        let noise = Perlin::new(rng);
+        FlowField::from_fn(area_size, cell_size, |point| {
+            let angle = noise.sample(point.x / noise_scale, point.y / noise_scale)
+                * std::f32::consts::PI
+                * 2.0;
+            Vector2::new(angle.cos() * strength, angle.sin() * strength)
+        })
+    }
+
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    /// Columns (and rows) a field over `area_size` with cells of `cell_size` would have.
+    pub fn column_count(area_size: f32, cell_size: f32) -> usize {
+        column_count(area_size, cell_size)
+    }
+
+    pub fn cell_size(&self) -> f32 {
+        self.cell_size
+    }
+
+    pub fn cell_center(&self, column: usize, row: usize) -> Vector2<f32> {
+        Vector2::new(
+            (column as f32 + 0.5) * self.cell_size,
+            (row as f32 + 0.5) * self.cell_size,
+        )
+    }
+
+    pub fn get(&self, column: usize, row: usize) -> Vector2<f32> {
+        self.vectors[row * self.columns + column]
+    }
+
+    pub fn set(&mut self, column: usize, row: usize, vector: Vector2<f32>) {
+        self.vectors[row * self.columns + column] = vector;
+    }
+
+    /// Vector of the cell containing `position`.
+    pub fn sample(&self, position: &Vector2<f32>) -> Vector2<f32> {
+        let coord = |value| cell_coord(value, self.cell_size, self.columns);
+        self.get(coord(position.x), coord(position.y))
+    }
+}
+
+// -----------------------------------------------------------------------------------------
+
+/// Classic 2D gradient noise with a permutation table shuffled by the given rng.
+struct Perlin {
+    permutation: Vec<usize>,
+}
+
+impl Perlin {
+    fn new(rng: &mut Rng) -> Self {
+        let mut permutation: Vec<usize> = (0..256).collect();
+        for i in (1..256).rev() {
+            let j = ((rng.next_f32() * (i + 1) as f32) as usize).min(i);
+            permutation.swap(i, j);
+        }
+
+        // doubled, so lookups of `hash + 1` never wrap
+        let doubled = permutation.clone();
+        permutation.extend(doubled);
+        Self { permutation }
+    }
+
+    fn fade(t: f32) -> f32 {
+        t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+    }
+
+    fn lerp(t: f32, a: f32, b: f32) -> f32 {
+        a + t * (b - a)
+    }
+
+    fn gradient(hash: usize, x: f32, y: f32) -> f32 {
+        match hash & 3 {
+            0 => x + y,
+            1 => -x + y,
+            2 => x - y,
+            _ => -x - y,
+        }
+    }
+
+    /// Noise value, roughly in `[-1, 1]`.
+    fn sample(&self, x: f32, y: f32) -> f32 {
+        let cell_x = (x.floor() as i64).rem_euclid(256) as usize;
+        let cell_y = (y.floor() as i64).rem_euclid(256) as usize;
+        let x = x - x.floor();
+        let y = y - y.floor();
+        let u = Perlin::fade(x);
+        let v = Perlin::fade(y);
+
+        let p = &self.permutation;
+        let a = p[cell_x] + cell_y;
+        let b = p[cell_x + 1] + cell_y;
+
+        Perlin::lerp(
+            v,
+            Perlin::lerp(
+                u,
+                Perlin::gradient(p[a], x, y),
+                Perlin::gradient(p[b], x - 1.0, y),
+            ),
+            Perlin::lerp(
+                u,
+                Perlin::gradient(p[a + 1], x, y - 1.0),
+                Perlin::gradient(p[b + 1], x - 1.0, y - 1.0),
+            ),
+        )
+    }
+}
@@ -0,0 +1,102 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use serde::Serialize;
+
+// -----------------------------------------------------------------------------------------
+
+/// A key going down or up, `time` in milliseconds as given by the browser event.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct KeyEvent {
+    pub key: String,
+    pub pressed: bool,
+    pub time: f32,
+}
+
+/// What the app does when a bound key is pressed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum KeyAction {
+    TogglePause,
+    Step,
+    Faster,
+    Slower,
+    ResetSpeed,
+    Reset,
+    ToggleDebug,
+    Spawn,
+}
+
+impl FromStr for KeyAction {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "pause" => Ok(KeyAction::TogglePause),
+            "step" => Ok(KeyAction::Step),
+            "faster" => Ok(KeyAction::Faster),
+            "slower" => Ok(KeyAction::Slower),
+            "reset_speed" => Ok(KeyAction::ResetSpeed),
+            "reset" => Ok(KeyAction::Reset),
+            "debug" => Ok(KeyAction::ToggleDebug),
+            "spawn" => Ok(KeyAction::Spawn),
+            other => Err(format!(
+                "unknown key action `{}`, expected one of: pause, step, faster, slower, reset_speed, reset, debug, spawn",
+                other
+            )),
+        }
+    }
+}
+
+// -----------------------------------------------------------------------------------------
+
+/// Maps `KeyboardEvent.key` values to actions. Keys are matched ignoring case, so
+/// bindings keep working with Shift or Caps Lock held.
+#[derive(Debug, Clone)]
+pub struct KeyBindings {
+    actions: HashMap<String, KeyAction>,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        let mut bindings = Self {
+            actions: HashMap::new(),
+        };
+        bindings.bind(" ", KeyAction::TogglePause);
+        bindings.bind(".", KeyAction::Step);
+        bindings.bind("+", KeyAction::Faster);
+        bindings.bind("=", KeyAction::Faster);
+        bindings.bind("-", KeyAction::Slower);
+        bindings.bind("1", KeyAction::ResetSpeed);
+        bindings.bind("r", KeyAction::Reset);
+        bindings.bind("d", KeyAction::ToggleDebug);
+        bindings.bind("s", KeyAction::Spawn);
+        bindings
+    }
+}
+
+impl KeyBindings {
+    /// Binds the key to the action, replacing what it was bound to before.
+    pub fn bind(&mut self, key: &str, action: KeyAction) {
+        self.actions.insert(key.to_lowercase(), action);
+    }
+
+    pub fn unbind(&mut self, key: &str) {
+        self.actions.remove(&key.to_lowercase());
+    }
+
+    pub fn action(&self, key: &str) -> Option<KeyAction> {
+        self.actions.get(&key.to_lowercase()).copied()
+    }
+
+    /// Actions of the pressed keys among `events`, in order. Releases trigger nothing.
+    pub fn actions<'a, I>(&'a self, events: I) -> impl Iterator<Item = KeyAction> + 'a
+    where
+        I: IntoIterator<Item = &'a KeyEvent>,
+        I::IntoIter: 'a,
+    {
+        events
+            .into_iter()
+            .filter(|event| event.pressed)
+            .filter_map(move |event| self.action(&event.key))
+    }
+}
@@ -0,0 +1,16 @@
+pub mod app_options;
+pub mod attractor;
+pub mod boundary;
+pub mod color;
+pub mod flock_params;
+pub mod flocking;
+pub mod flow_field;
+pub mod keyboard;
+pub mod obstacle;
+pub mod path;
+pub mod predator;
+pub mod rng;
+mod spatial_grid;
+pub mod species;
+pub mod timestep;
+pub mod vector2;
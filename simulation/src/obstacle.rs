@@ -0,0 +1,72 @@
+use crate::vector2::Vector2;
+
+// -----------------------------------------------------------------------------------------
+
+/// Static shape the boids steer around.
+#[derive(Debug, Clone, Copy)]
+pub enum Obstacle {
+    Circle {
+        center: Vector2<f32>,
+        radius: f32,
+    },
+    /// Axis-aligned rectangle spanning from `min` to `max`.
+    Rectangle {
+        min: Vector2<f32>,
+        max: Vector2<f32>,
+    },
+}
+
+impl Obstacle {
+    pub fn circle(center: Vector2<f32>, radius: f32) -> Self {
+        Obstacle::Circle { center, radius }
+    }
+
+    pub fn rectangle(position: Vector2<f32>, size: Vector2<f32>) -> Self {
+        Obstacle::Rectangle {
+            min: position,
+            max: Vector2::new(position.x + size.x, position.y + size.y),
+        }
+    }
+
+    pub fn center(&self) -> Vector2<f32> {
+        match self {
+            Obstacle::Circle { center, .. } => *center,
+            Obstacle::Rectangle { min, max } => {
+                Vector2::new((min.x + max.x) / 2.0, (min.y + max.y) / 2.0)
+            }
+        }
+    }
+
+    /// Distance from `point` to the edge of the obstacle, negative inside of it.
+    pub fn distance_to(&self, point: &Vector2<f32>) -> f32 {
+        match self {
+            Obstacle::Circle { center, radius } => point.distance_to(center) - radius,
+            Obstacle::Rectangle { min, max } => {
+                let dx = (min.x - point.x).max(point.x - max.x);
+                let dy = (min.y - point.y).max(point.y - max.y);
+                if dx > 0.0 || dy > 0.0 {
+                    Vector2::new(dx.max(0.0), dy.max(0.0)).mag()
+                } else {
+                    dx.max(dy)
+                }
+            }
+        }
+    }
+
+    /// Direction from the obstacle towards `point`, not normalized.
+    pub fn away_from(&self, point: &Vector2<f32>) -> Vector2<f32> {
+        match self {
+            Obstacle::Circle { center, .. } => *point - *center,
+            Obstacle::Rectangle { min, max } => {
+                let closest =
+                    Vector2::new(point.x.max(min.x).min(max.x), point.y.max(min.y).min(max.y));
+                let away = *point - closest;
+                if away.mag() > 0.0 {
+                    away
+                } else {
+                    *point - self.center()
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use crate::vector2::Vector2;
+
+// -----------------------------------------------------------------------------------------
+
+/// Polyline the flock patrols along, staying within `radius` of it. A closed path also
+/// has a segment from the last point back to the first one.
+#[derive(Debug, Clone)]
+pub struct Path {
+    points: Vec<Vector2<f32>>,
+    pub radius: f32,
+    pub closed: bool,
+}
+
+impl Path {
+    pub fn new(points: Vec<Vector2<f32>>, radius: f32, closed: bool) -> Self {
+        Self {
+            points,
+            radius,
+            closed,
+        }
+    }
+
+    pub fn points(&self) -> &[Vector2<f32>] {
+        &self.points
+    }
+
+    pub fn add_point(&mut self, point: Vector2<f32>) {
+        self.points.push(point);
+    }
+
+    fn segments(&self) -> impl Iterator<Item = (Vector2<f32>, Vector2<f32>)> + '_ {
+        let closing = if self.closed && self.points.len() > 2 {
+            Some((self.points[self.points.len() - 1], self.points[0]))
+        } else {
+            None
+        };
+
+        self.points
+            .windows(2)
+            .map(|pair| (pair[0], pair[1]))
+            .chain(closing)
+    }
+
+    /// Closest point of the path to `point`, with the unit direction of its segment.
+    /// `None` for paths with less than two points.
+    pub fn project(&self, point: &Vector2<f32>) -> Option<(Vector2<f32>, Vector2<f32>)> {
+        let mut closest: Option<(f32, Vector2<f32>, Vector2<f32>)> = None;
+
+        for (start, end) in self.segments() {
+            let mut direction = end - start;
+            let length = direction.mag();
+            if length <= 0.0 {
+                continue;
+            }
+            direction.normalize();
+
+            let offset = *point - start;
+            let along = (offset.x * direction.x + offset.y * direction.y)
+                .max(0.0)
+                .min(length);
+            let mut normal_point = start;
+            normal_point += direction * along;
+
+            let distance = normal_point.distance_to(point);
+            let closer = match closest {
+                Some((best, _, _)) => distance < best,
+                None => true,
+            };
+            if closer {
+                closest = Some((distance, normal_point, direction));
+            }
+        }
+
+        closest.map(|(_, normal_point, direction)| (normal_point, direction))
+    }
+}
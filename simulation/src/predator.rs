@@ -0,0 +1,54 @@
+use crate::flock_params::FlockParams;
+use crate::flocking::{integrate, interpolate, Step};
+use crate::rng::Rng;
+use crate::vector2::Vector2;
+
+// -----------------------------------------------------------------------------------------
+
+/// Agent hunting the flock. Boids inside `FlockParams::flee_radius` of a predator run
+/// away from it.
+#[derive(Debug, Clone, Copy)]
+pub struct Predator {
+    pub acceleration: Vector2<f32>,
+    pub velocity: Vector2<f32>,
+    pub position: Vector2<f32>,
+    pub previous_position: Vector2<f32>,
+}
+
+impl Predator {
+    pub fn new(position: &Vector2<f32>, rng: &mut Rng) -> Self {
+        let direction = rng.range(0.0, 360.0);
+        Self {
+            velocity: Vector2::from_angle(direction),
+            position: *position,
+            previous_position: *position,
+            acceleration: Vector2::zero(),
+        }
+    }
+
+    /// Seek-style pursuit using the predator's own speed and force limits.
+    pub fn pursue(&self, target: &Vector2<f32>, params: &FlockParams) -> Vector2<f32> {
+        let mut desired = *target - self.position;
+        desired.normalize();
+        desired *= params.predator_max_speed;
+        let mut steer = desired - self.velocity;
+        steer.limit(params.predator_max_force);
+        steer
+    }
+
+    pub(crate) fn integrate(&mut self, step: &Step, params: &FlockParams) -> bool {
+        integrate(
+            &mut self.position,
+            &mut self.previous_position,
+            &mut self.velocity,
+            &mut self.acceleration,
+            params.predator_max_speed,
+            step,
+        )
+    }
+
+    /// Where to draw the predator between two updates, like `Boid::interpolated_position`.
+    pub fn interpolated_position(&self, alpha: f32) -> Vector2<f32> {
+        interpolate(&self.previous_position, &self.position, alpha)
+    }
+}
@@ -0,0 +1,119 @@
+use crate::vector2::Vector2;
+
+// -----------------------------------------------------------------------------------------
+
+/// Uniform grid over the square scene `[0, area_size]`, used to find boids that may lie
+/// within a given radius without scanning the whole flock. Positions outside the scene
+/// are clamped into the border cells.
+#[derive(Debug, Clone)]
+pub struct SpatialGrid {
+    area_size: f32,
+    cell_size: f32,
+    columns: usize,
+    cells: Vec<Vec<usize>>,
+}
+
+impl SpatialGrid {
+    pub fn new(area_size: f32, cell_size: f32) -> Self {
+        let mut grid = Self {
+            area_size: 0.0,
+            cell_size,
+            columns: 0,
+            cells: vec![],
+        };
+        grid.resize(area_size);
+        grid
+    }
+
+    fn resize(&mut self, area_size: f32) {
+        self.area_size = area_size;
+        self.columns = column_count(area_size, self.cell_size);
+        self.cells = vec![vec![]; self.columns * self.columns];
+    }
+
+    pub fn set_cell_size(&mut self, cell_size: f32) {
+        if (cell_size - self.cell_size).abs() > f32::EPSILON {
+            self.cell_size = cell_size;
+            self.resize(self.area_size);
+        }
+    }
+
+    fn cell_index(&self, position: &Vector2<f32>) -> usize {
+        let coord = |value| cell_coord(value, self.cell_size, self.columns);
+        coord(position.y) * self.columns + coord(position.x)
+    }
+
+    /// Clears the grid and inserts every position under its index in the iterator.
+    pub fn rebuild<'a, I>(&mut self, area_size: f32, positions: I)
+    where
+        I: Iterator<Item = &'a Vector2<f32>>,
+    {
+        if (area_size - self.area_size).abs() > f32::EPSILON {
+            self.resize(area_size);
+        }
+
+        for cell in self.cells.iter_mut() {
+            cell.clear();
+        }
+
+        for (index, position) in positions.enumerate() {
+            let cell = self.cell_index(position);
+            self.cells[cell].push(index);
+        }
+    }
+
+    /// Moves an already inserted index to the cell of its new position.
+    pub fn relocate(
+        &mut self,
+        index: usize,
+        old_position: &Vector2<f32>,
+        new_position: &Vector2<f32>,
+    ) {
+        let old_cell = self.cell_index(old_position);
+        let new_cell = self.cell_index(new_position);
+
+        if old_cell == new_cell {
+            return;
+        }
+
+        if let Some(slot) = self.cells[old_cell].iter().position(|&i| i == index) {
+            self.cells[old_cell].swap_remove(slot);
+        }
+        self.cells[new_cell].push(index);
+    }
+
+    /// Writes into `result` every index stored in the cells overlapping the square
+    /// `position ± radius`. Callers still have to check the exact distance.
+    pub fn query(&self, position: &Vector2<f32>, radius: f32, result: &mut Vec<usize>) {
+        result.clear();
+
+        let coord = |value| cell_coord(value, self.cell_size, self.columns);
+        let min_x = coord(position.x - radius);
+        let max_x = coord(position.x + radius);
+        let min_y = coord(position.y - radius);
+        let max_y = coord(position.y + radius);
+
+        for row in min_y..=max_y {
+            for column in min_x..=max_x {
+                result.extend_from_slice(&self.cells[row * self.columns + column]);
+            }
+        }
+    }
+}
+
+// -----------------------------------------------------------------------------------------
+
+/// Columns of `cell_size` needed to cover `[0, area_size]`, at least one.
+pub(crate) fn column_count(area_size: f32, cell_size: f32) -> usize {
+    ((area_size / cell_size).ceil() as usize).max(1)
+}
+
+/// Column or row of the cell containing `value`, clamped into the grid.
+pub(crate) fn cell_coord(value: f32, cell_size: f32, columns: usize) -> usize {
+    let coord = (value / cell_size).floor();
+    if coord <= 0.0 {
+        0
+    } else {
+        (coord as usize).min(columns - 1)
+    }
+}
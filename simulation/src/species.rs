@@ -0,0 +1,136 @@
+use std::str::FromStr;
+
+use crate::color::Color;
+
+// -----------------------------------------------------------------------------------------
+
+/// Look of the boids of one species. `texture` indexes the textures passed to the renderer,
+/// and a `tint` is drawn as a disc of that color under each of the species' boids.
+#[derive(Debug, Clone, Copy)]
+pub struct Species {
+    pub texture: usize,
+    pub tint: Option<Color>,
+}
+
+impl Species {
+    pub fn new(texture: usize, tint: Option<Color>) -> Self {
+        Self { texture, tint }
+    }
+}
+
+// -----------------------------------------------------------------------------------------
+
+/// Which of the neighbour rules a boid applies to neighbours of a given species.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Interaction {
+    pub align: bool,
+    pub cohere: bool,
+    pub avoid: bool,
+}
+
+impl Interaction {
+    pub const FLOCK: Interaction = Interaction {
+        align: true,
+        cohere: true,
+        avoid: true,
+    };
+    pub const AVOID: Interaction = Interaction {
+        align: false,
+        cohere: false,
+        avoid: true,
+    };
+    pub const IGNORE: Interaction = Interaction {
+        align: false,
+        cohere: false,
+        avoid: false,
+    };
+
+    pub fn ignores(&self) -> bool {
+        *self == Interaction::IGNORE
+    }
+}
+
+/// Parses either `flock`, `ignore`, or rules joined by `+`, like `align+avoid`.
+impl FromStr for Interaction {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "flock" => return Ok(Interaction::FLOCK),
+            "ignore" => return Ok(Interaction::IGNORE),
+            _ => {}
+        }
+
+        let mut interaction = Interaction::IGNORE;
+        for rule in value.split('+') {
+            match rule.trim() {
+                "align" => interaction.align = true,
+                "cohere" => interaction.cohere = true,
+                "avoid" => interaction.avoid = true,
+                other => {
+                    return Err(format!(
+                        "unknown interaction `{}`, expected flock, ignore, or a `+` separated list of: align, cohere, avoid",
+                        other
+                    ))
+                }
+            }
+        }
+        Ok(interaction)
+    }
+}
+
+// -----------------------------------------------------------------------------------------
+
+/// Square table of the interaction of every species (rows) with every species (columns).
+/// Boids flock with their own species and only keep their distance from the others,
+/// unless told otherwise.
+#[derive(Debug, Clone)]
+pub struct InteractionMatrix {
+    species_count: usize,
+    interactions: Vec<Interaction>,
+}
+
+impl InteractionMatrix {
+    pub fn new(species_count: usize) -> Self {
+        let mut matrix = Self {
+            species_count: 0,
+            interactions: vec![],
+        };
+        matrix.resize(species_count);
+        matrix
+    }
+
+    pub fn species_count(&self) -> usize {
+        self.species_count
+    }
+
+    /// Changes the number of species, keeping the interactions already set between the
+    /// remaining ones.
+    pub fn resize(&mut self, species_count: usize) {
+        let mut interactions = Vec::with_capacity(species_count * species_count);
+        for observer in 0..species_count {
+            for other in 0..species_count {
+                let interaction = if observer < self.species_count && other < self.species_count {
+                    self.get(observer, other)
+                } else if observer == other {
+                    Interaction::FLOCK
+                } else {
+                    Interaction::AVOID
+                };
+                interactions.push(interaction);
+            }
+        }
+
+        self.species_count = species_count;
+        self.interactions = interactions;
+    }
+
+    /// How boids of the `observer` species treat neighbours of the `other` species.
+    pub fn get(&self, observer: usize, other: usize) -> Interaction {
+        self.interactions[observer * self.species_count + other]
+    }
+
+    pub fn set(&mut self, observer: usize, other: usize, interaction: Interaction) {
+        self.interactions[observer * self.species_count + other] = interaction;
+    }
+}
@@ -0,0 +1,87 @@
+/// Fastest the simulation may run relative to real time.
+pub const MAX_TIME_SCALE: f32 = 16.0;
+
+// -----------------------------------------------------------------------------------------
+
+/// Turns the irregular time between frames into a whole number of fixed simulation steps.
+/// The remainder carries over to the next frame, and `alpha` tells how far the renderer
+/// is between the last two steps.
+#[derive(Debug, Clone, Copy)]
+pub struct FixedTimestep {
+    step: f32,
+    max_steps: u32,
+    accumulator: f32,
+}
+
+impl FixedTimestep {
+    pub fn new(step: f32, max_steps: u32) -> Self {
+        Self {
+            step,
+            max_steps,
+            accumulator: 0.0,
+        }
+    }
+
+    pub fn step(&self) -> f32 {
+        self.step
+    }
+
+    pub fn set_step(&mut self, step: f32) -> Result<(), String> {
+        if !step.is_finite() || step <= 0.0 {
+            return Err(format!("time step must be a positive number, got {}", step));
+        }
+        self.step = step;
+        self.accumulator = self.accumulator.min(step);
+        Ok(())
+    }
+
+    pub fn max_steps(&self) -> u32 {
+        self.max_steps
+    }
+
+    pub fn set_max_steps(&mut self, max_steps: u32) -> Result<(), String> {
+        if max_steps == 0 {
+            return Err("the simulation needs at least one step per update".to_string());
+        }
+        self.max_steps = max_steps;
+        Ok(())
+    }
+
+    /// Adds the elapsed time and returns how many steps to simulate. Time which would
+    /// need more than `max_steps` is dropped, so a tab coming back from the background
+    /// does not freeze while catching up.
+    pub fn advance(&mut self, elapsed: f32) -> u32 {
+        self.advance_at_most(elapsed, self.max_steps)
+    }
+
+    /// Like `advance` for `elapsed` real seconds simulated `time_scale` times faster. The
+    /// step limit grows with the scale, so a sped up simulation is not cut short by it.
+    /// Scales above `MAX_TIME_SCALE` run at `MAX_TIME_SCALE`, which bounds the limit.
+    pub fn advance_scaled(&mut self, elapsed: f32, time_scale: f32) -> u32 {
+        let time_scale = time_scale.min(MAX_TIME_SCALE);
+        let max_steps = (self.max_steps as f32 * time_scale.max(1.0)).ceil() as u32;
+        self.advance_at_most(elapsed * time_scale, max_steps)
+    }
+
+    fn advance_at_most(&mut self, elapsed: f32, max_steps: u32) -> u32 {
+        if elapsed.is_finite() && elapsed > 0.0 {
+            self.accumulator += elapsed;
+        }
+
+        let mut steps = 0;
+        while self.accumulator >= self.step && steps < max_steps {
+            self.accumulator -= self.step;
+            steps += 1;
+        }
+
+        if steps == max_steps {
+            self.accumulator %= self.step;
+        }
+        steps
+    }
+
+    /// Fraction of a step accumulated since the last simulated one, in `[0, 1]`.
+    pub fn alpha(&self) -> f32 {
+        (self.accumulator / self.step).min(1.0)
+    }
+}
@@ -0,0 +1,110 @@
+use std::ops::{Add, AddAssign, DivAssign, Mul, MulAssign, Sub, SubAssign};
+
+// -----------------------------------------------------------------------------------------
+
+/// 2D vector of the simulation. It has the same shape as the swgl one taken by the
+/// renderer, so the simulation builds without the WebGL library.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Vector2<T> {
+    pub x: T,
+    pub y: T,
+}
+
+impl Vector2<f32> {
+    pub fn new(x: f32, y: f32) -> Self {
+        Self { x, y }
+    }
+
+    pub fn zero() -> Self {
+        Self { x: 0.0, y: 0.0 }
+    }
+
+    /// Unit vector pointing along `angle`, in radians.
+    pub fn from_angle(angle: f32) -> Self {
+        Self {
+            x: angle.cos(),
+            y: angle.sin(),
+        }
+    }
+
+    pub fn mag(&self) -> f32 {
+        (self.x * self.x + self.y * self.y).sqrt()
+    }
+
+    /// Scales the vector to unit length. A zero vector stays zero.
+    pub fn normalize(&mut self) {
+        let mag = self.mag();
+        if mag > 0.0 {
+            *self /= mag;
+        }
+    }
+
+    /// Scales the vector down to `max` length if it is longer.
+    pub fn limit(&mut self, max: f32) {
+        let mag = self.mag();
+        if mag > max {
+            *self *= max / mag;
+        }
+    }
+
+    /// Angle of the vector, in radians.
+    pub fn heading(&self) -> f32 {
+        self.y.atan2(self.x)
+    }
+
+    pub fn distance_to(&self, other: &Self) -> f32 {
+        (*self - *other).mag()
+    }
+}
+
+impl Add for Vector2<f32> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl Sub for Vector2<f32> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl Mul<f32> for Vector2<f32> {
+    type Output = Self;
+
+    fn mul(self, scalar: f32) -> Self {
+        Self::new(self.x * scalar, self.y * scalar)
+    }
+}
+
+impl AddAssign for Vector2<f32> {
+    fn add_assign(&mut self, other: Self) {
+        self.x += other.x;
+        self.y += other.y;
+    }
+}
+
+impl SubAssign for Vector2<f32> {
+    fn sub_assign(&mut self, other: Self) {
+        self.x -= other.x;
+        self.y -= other.y;
+    }
+}
+
+impl MulAssign<f32> for Vector2<f32> {
+    fn mul_assign(&mut self, scalar: f32) {
+        self.x *= scalar;
+        self.y *= scalar;
+    }
+}
+
+impl DivAssign<f32> for Vector2<f32> {
+    fn div_assign(&mut self, scalar: f32) {
+        self.x /= scalar;
+        self.y /= scalar;
+    }
+}
@@ -0,0 +1,117 @@
+//! Runs the `flock-sim` binary and checks its arguments and output.
+
+use std::collections::HashSet;
+use std::process::{Command, Output};
+
+use serde_json::Value;
+
+// -----------------------------------------------------------------------------------------
+
+fn flock_sim(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_flock-sim"))
+        .args(args)
+        .output()
+        .expect("flock-sim should start")
+}
+
+fn stdout_lines(output: &Output) -> Vec<String> {
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout.clone())
+        .unwrap()
+        .lines()
+        .map(str::to_owned)
+        .collect()
+}
+
+fn assert_rejected(args: &[&str], message: &str) {
+    let output = flock_sim(args);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr.contains(message), "{}", stderr);
+}
+
+// -----------------------------------------------------------------------------------------
+
+#[test]
+fn csv_has_a_row_per_boid_and_tick() {
+    let lines = stdout_lines(&flock_sim(&["--boids", "3", "--ticks", "2"]));
+
+    assert_eq!(lines[0], "tick,boid,x,y,vx,vy");
+    assert_eq!(lines.len(), 1 + 3 * 3);
+    for (row, line) in lines[1..].iter().enumerate() {
+        let fields: Vec<&str> = line.split(',').collect();
+        assert_eq!(fields.len(), 6);
+        assert_eq!(fields[0], (row / 3).to_string());
+        assert_eq!(fields[1], (row % 3).to_string());
+        for field in &fields[2..] {
+            assert!(field.parse::<f32>().unwrap().is_finite());
+        }
+    }
+}
+
+#[test]
+fn jsonl_matches_csv() {
+    let args = ["--boids", "4", "--ticks", "3", "--seed", "11"];
+    let csv = stdout_lines(&flock_sim(&args));
+    let jsonl = stdout_lines(&flock_sim(&[&args[..], &["--format", "jsonl"]].concat()));
+
+    assert_eq!(jsonl.len(), csv.len() - 1);
+    for (json, csv) in jsonl.iter().zip(csv[1..].iter()) {
+        let json: Value = serde_json::from_str(json).unwrap();
+        let fields: Vec<&str> = csv.split(',').collect();
+        for (key, field) in ["tick", "boid", "x", "y", "vx", "vy"].iter().zip(fields) {
+            assert_eq!(json[key].as_f64().unwrap(), field.parse::<f64>().unwrap());
+        }
+    }
+}
+
+#[test]
+fn boid_ids_survive_boids_leaving_an_open_scene() {
+    let lines = stdout_lines(&flock_sim(&[
+        "--boids",
+        "20",
+        "--ticks",
+        "400",
+        "--dt",
+        "1",
+        "--area",
+        "200",
+        "--border",
+        "0",
+        "--boundary",
+        "open",
+    ]));
+
+    let mut ids_by_tick: Vec<HashSet<usize>> = vec![];
+    for line in &lines[1..] {
+        let fields: Vec<&str> = line.split(',').collect();
+        let tick: usize = fields[0].parse().unwrap();
+        if ids_by_tick.len() <= tick {
+            ids_by_tick.push(HashSet::new());
+        }
+        assert!(ids_by_tick[tick].insert(fields[1].parse().unwrap()));
+    }
+
+    let first = &ids_by_tick[0];
+    let last = ids_by_tick.last().unwrap();
+    assert!(last.len() < first.len(), "no boid left the scene");
+    for ids in ids_by_tick.windows(2) {
+        assert!(ids[1].is_subset(&ids[0]));
+    }
+    // with Vec indices the survivors would be renumbered from 0
+    assert!(last.iter().any(|id| *id >= last.len()));
+}
+
+#[test]
+fn bad_arguments_are_rejected() {
+    assert_rejected(&["--boids"], "missing value for --boids");
+    assert_rejected(&["--ticks", "many"], "invalid value for --ticks: many");
+    assert_rejected(&["--dt", "0"], "--dt must be positive");
+    assert_rejected(&["--format", "xml"], "unknown format: xml");
+    assert_rejected(&["--boundary", "bounce"], "bounce");
+    assert_rejected(&["--fast"], "unknown argument: --fast");
+}
@@ -0,0 +1,736 @@
+//! Native tests of the flocking simulation.
+
+use flocking_simulation::app_options::AppOptions;
+use flocking_simulation::attractor::Attractor;
+use flocking_simulation::boundary::BoundaryMode;
+use flocking_simulation::color::Color;
+use flocking_simulation::flock_params::FlockParams;
+use flocking_simulation::flocking::{Boid, Cursor, Flock, NeighbourSearch, UpdateMode};
+use flocking_simulation::flow_field::FlowField;
+use flocking_simulation::keyboard::{KeyAction, KeyBindings, KeyEvent};
+use flocking_simulation::obstacle::Obstacle;
+use flocking_simulation::path::Path;
+use flocking_simulation::predator::Predator;
+use flocking_simulation::rng::Rng;
+use flocking_simulation::species::{Interaction, Species};
+use flocking_simulation::timestep::FixedTimestep;
+use flocking_simulation::vector2::Vector2;
+
+mod reference;
+
+use reference::{reference_align, reference_cohesion, reference_separate};
+
+const AREA_SIZE: f32 = 1000.0;
+const BORDER_THICK: f32 = 50.0;
+const DELTA_TIME: f32 = 1.0 / 60.0;
+
+// -----------------------------------------------------------------------------------------
+
+fn test_boid(position: Vector2<f32>, velocity: Vector2<f32>) -> Boid {
+    Boid::with_velocity(&position, &velocity)
+}
+
+fn random_flock(count: usize, seed: u64) -> Flock {
+    let mut rng = Rng::new(seed);
+
+    let boids = (0..count)
+        .map(|_| {
+            let position = Vector2::new(
+                BORDER_THICK + rng.next_f32() * (AREA_SIZE - 2.0 * BORDER_THICK),
+                BORDER_THICK + rng.next_f32() * (AREA_SIZE - 2.0 * BORDER_THICK),
+            );
+            let angle = rng.next_f32() * std::f32::consts::PI * 2.0;
+            let velocity = Vector2::new(angle.cos(), angle.sin());
+            test_boid(position, velocity)
+        })
+        .collect();
+
+    Flock::from_boids(boids, AREA_SIZE)
+}
+
+fn assert_flocks_close(left: &Flock, right: &Flock, tolerance: f32) {
+    assert_boids_close(left.boids(), right.boids(), tolerance);
+}
+
+fn assert_boids_close(left: &[Boid], right: &[Boid], tolerance: f32) {
+    assert_eq!(left.len(), right.len());
+    for (a, b) in left.iter().zip(right.iter()) {
+        assert!((a.position.x - b.position.x).abs() <= tolerance);
+        assert!((a.position.y - b.position.y).abs() <= tolerance);
+        assert!((a.velocity.x - b.velocity.x).abs() <= tolerance);
+        assert!((a.velocity.y - b.velocity.y).abs() <= tolerance);
+    }
+}
+
+// -----------------------------------------------------------------------------------------
+
+#[test]
+fn grid_search_matches_brute_force() {
+    let mut brute_force = random_flock(400, 0x9e3779b9);
+    brute_force.set_neighbour_search(NeighbourSearch::BruteForce);
+    let mut grid = brute_force.clone();
+    grid.set_neighbour_search(NeighbourSearch::Grid);
+
+    let cursors = [Cursor::repel(Vector2::new(
+        AREA_SIZE / 2.0,
+        AREA_SIZE / 2.0,
+    ))];
+
+    for _ in 0..100 {
+        brute_force.update(DELTA_TIME, AREA_SIZE, BORDER_THICK, &cursors);
+        grid.update(DELTA_TIME, AREA_SIZE, BORDER_THICK, &cursors);
+        assert_flocks_close(&brute_force, &grid, 1e-3);
+    }
+}
+
+#[test]
+fn sequential_grid_update_sees_relocated_boids() {
+    let mut grid = random_flock(400, 0x27d4eb2f);
+    // small cells, so a boid left in its old cell falls out of the queries
+    let params = FlockParams {
+        neighborhood_zone: 40.0,
+        separation_zone: 20.0,
+        ..FlockParams::default()
+    };
+    grid.set_params(params).unwrap();
+    grid.set_update_mode(UpdateMode::Sequential);
+    grid.set_neighbour_search(NeighbourSearch::Grid);
+
+    let cell_size = grid.params().neighborhood_zone;
+    let cell = |position: &Vector2<f32>| {
+        (
+            (position.x / cell_size).floor() as i32,
+            (position.y / cell_size).floor() as i32,
+        )
+    };
+
+    // large steps, so boids change cells in the middle of a tick
+    let delta_time = 5.0;
+    let mut cell_changes = 0;
+    for _ in 0..100 {
+        // compared one tick at a time, so rounding differences do not pile up
+        let mut brute_force = grid.clone();
+        brute_force.set_neighbour_search(NeighbourSearch::BruteForce);
+        let before: Vec<_> = grid
+            .boids()
+            .iter()
+            .map(|boid| cell(&boid.position))
+            .collect();
+
+        brute_force.update(delta_time, AREA_SIZE, BORDER_THICK, &[]);
+        grid.update(delta_time, AREA_SIZE, BORDER_THICK, &[]);
+        assert_flocks_close(&brute_force, &grid, 1e-3);
+
+        cell_changes += grid
+            .boids()
+            .iter()
+            .zip(before.iter())
+            .filter(|(boid, before)| cell(&boid.position) != **before)
+            .count();
+    }
+    assert!(cell_changes > 0);
+}
+
+#[test]
+fn fused_flock_force_matches_separate_rules() {
+    let flock = random_flock(200, 0x2545f491);
+    let neighbours: Vec<usize> = (0..flock.boids().len()).collect();
+    let cursor = Vector2::new(AREA_SIZE / 2.0, AREA_SIZE / 2.0);
+    let cursors = [Cursor::repel(cursor)];
+    let params = flock.params();
+
+    for index in 0..flock.boids().len() {
+        let fused = flock.flock(index, &neighbours, &cursors);
+
+        let boids = flock.boids();
+        let mut separate = Vector2::zero();
+        separate += reference_cohesion(boids, index, params) * params.cohesion_weight;
+        separate += reference_separate(boids, index, params) * params.separate_weight;
+        separate += reference_align(boids, index, params) * params.align_weight;
+        separate += flock.separate_from_cursor(index, &cursor) * params.cursor_separate_weight;
+
+        assert!((fused.x - separate.x).abs() <= 1e-6);
+        assert!((fused.y - separate.y).abs() <= 1e-6);
+    }
+}
+
+#[test]
+fn synchronous_update_does_not_depend_on_boid_order() {
+    let flock = random_flock(150, 0x85ebca6b);
+    let count = flock.boids().len();
+
+    // a fixed shuffle of the boid indices
+    let mut rng = Rng::new(0xc2b2ae35);
+    let mut permutation: Vec<usize> = (0..count).collect();
+    for i in (1..count).rev() {
+        let j = ((rng.next_f32() * (i + 1) as f32) as usize).min(i);
+        permutation.swap(i, j);
+    }
+
+    let mut original = flock;
+    original.set_update_mode(UpdateMode::Synchronous);
+    let permuted_boids = permutation.iter().map(|&i| original.boids()[i]).collect();
+    let mut permuted = Flock::from_boids(permuted_boids, AREA_SIZE);
+    permuted.set_update_mode(UpdateMode::Synchronous);
+
+    let cursors = [Cursor::repel(Vector2::new(
+        AREA_SIZE / 2.0,
+        AREA_SIZE / 2.0,
+    ))];
+
+    for _ in 0..50 {
+        original.update(DELTA_TIME, AREA_SIZE, BORDER_THICK, &cursors);
+        permuted.update(DELTA_TIME, AREA_SIZE, BORDER_THICK, &cursors);
+
+        let unpermuted: Vec<Boid> = permutation.iter().map(|&i| original.boids()[i]).collect();
+        assert_boids_close(&unpermuted, permuted.boids(), 1e-3);
+    }
+}
+
+#[test]
+fn flock_params_are_validated() {
+    let mut flock = random_flock(10, 0x27d4eb2f);
+
+    let params = FlockParams {
+        max_speed: -1.0,
+        ..FlockParams::default()
+    };
+    assert!(flock.set_params(params).is_err());
+
+    let mut params = FlockParams::default();
+    params.separation_zone = params.neighborhood_zone + 1.0;
+    assert!(flock.set_params(params).is_err());
+
+    let params = FlockParams {
+        align_weight: 2.5,
+        ..FlockParams::default()
+    };
+    assert!(flock.set_params(params).is_ok());
+    assert_eq!(flock.params().align_weight, 2.5);
+}
+
+#[test]
+fn same_seed_gives_identical_trajectories() {
+    let run = |seed: u64| {
+        let mut flock = Flock::new(50, AREA_SIZE, seed);
+        let cursors = [Cursor::repel(Vector2::new(
+            AREA_SIZE / 3.0,
+            AREA_SIZE / 3.0,
+        ))];
+        let mut trajectory = vec![];
+
+        for _ in 0..1000 {
+            flock.update(DELTA_TIME, AREA_SIZE, BORDER_THICK, &cursors);
+            for boid in flock.boids() {
+                trajectory.push(boid.position.x.to_bits());
+                trajectory.push(boid.position.y.to_bits());
+                trajectory.push(boid.velocity.x.to_bits());
+                trajectory.push(boid.velocity.y.to_bits());
+            }
+        }
+
+        trajectory
+    };
+
+    assert_eq!(run(42), run(42));
+    assert_ne!(run(42), run(43));
+}
+
+#[test]
+fn boids_steer_around_obstacles() {
+    let rock = Obstacle::circle(Vector2::new(500.0, 510.0), 60.0);
+
+    for &start_y in [480.0, 510.0, 540.0].iter() {
+        let boid = test_boid(Vector2::new(200.0, start_y), Vector2::new(200.0, 0.0));
+        let mut flock = Flock::from_boids(vec![boid], AREA_SIZE);
+        flock.add_obstacle(rock);
+
+        for _ in 0..3000 {
+            flock.update(0.001, AREA_SIZE, BORDER_THICK, &[]);
+            assert!(rock.distance_to(&flock.boids()[0].position) > 0.0);
+        }
+    }
+}
+
+#[test]
+fn predators_chase_and_boids_flee() {
+    let boid = test_boid(Vector2::new(560.0, 500.0), Vector2::new(0.0, 1.0));
+    let mut flock = Flock::from_boids(vec![boid], AREA_SIZE);
+
+    let mut predator = Predator::new(&Vector2::new(500.0, 500.0), &mut Rng::new(1));
+    predator.velocity = Vector2::new(0.0, -1.0);
+    flock.add_predator(predator);
+
+    for _ in 0..200 {
+        flock.update(0.001, AREA_SIZE, BORDER_THICK, &[]);
+    }
+
+    assert!(flock.boids()[0].velocity.x > 0.0);
+    assert!(flock.predators()[0].velocity.x > 0.0);
+}
+
+#[test]
+fn boundary_modes_handle_a_boid_leaving_the_scene() {
+    let run = |mode: BoundaryMode| {
+        let boid = test_boid(Vector2::new(900.0, 500.0), Vector2::new(200.0, 0.0));
+        let mut flock = Flock::from_boids(vec![boid], AREA_SIZE);
+        flock.set_boundary_mode(mode);
+
+        let mut max_x: f32 = 0.0;
+        for _ in 0..3000 {
+            flock.update(0.001, AREA_SIZE, BORDER_THICK, &[]);
+            if let Some(boid) = flock.boids().first() {
+                max_x = max_x.max(boid.position.x);
+            }
+        }
+        (flock, max_x)
+    };
+
+    let (wrapped, _) = run(BoundaryMode::Wrap);
+    assert!(wrapped.boids()[0].position.x < 900.0);
+
+    let (reflected, max_x) = run(BoundaryMode::Reflect);
+    assert!(max_x <= AREA_SIZE - BORDER_THICK);
+    assert!(reflected.boids()[0].velocity.x < 0.0);
+
+    let (steered, max_x) = run(BoundaryMode::Steer);
+    assert!(max_x < AREA_SIZE);
+    assert!(steered.boids()[0].velocity.x < 0.0);
+
+    let (open, _) = run(BoundaryMode::Open);
+    assert!(open.boids().is_empty());
+}
+
+#[test]
+fn boids_ignore_neighbours_in_their_blind_spot() {
+    let boids = vec![
+        test_boid(Vector2::new(500.0, 500.0), Vector2::new(100.0, 0.0)),
+        test_boid(Vector2::new(450.0, 500.0), Vector2::new(0.0, 100.0)),
+    ];
+    let mut flock = Flock::from_boids(boids, AREA_SIZE);
+    let neighbours = [0, 1];
+
+    assert!(flock.cohesion(0, &neighbours).mag() > 0.0);
+    assert!(flock.separate(0, &neighbours).mag() > 0.0);
+    assert!(flock.align(0, &neighbours).mag() > 0.0);
+
+    let params = FlockParams {
+        view_angle: 270.0,
+        ..FlockParams::default()
+    };
+    flock.set_params(params).unwrap();
+
+    assert_eq!(flock.cohesion(0, &neighbours).mag(), 0.0);
+    assert_eq!(flock.separate(0, &neighbours).mag(), 0.0);
+    assert_eq!(flock.align(0, &neighbours).mag(), 0.0);
+
+    // the boid behind still sees the first one in front of it
+    assert!(flock.cohesion(1, &neighbours).mag() > 0.0);
+}
+
+#[test]
+fn species_follow_the_interaction_matrix() {
+    let mut other = test_boid(Vector2::new(540.0, 500.0), Vector2::new(0.0, 100.0));
+    other.species = 1;
+    let boids = vec![
+        test_boid(Vector2::new(500.0, 500.0), Vector2::new(100.0, 0.0)),
+        other,
+    ];
+    let mut flock = Flock::from_boids(boids, AREA_SIZE);
+    let neighbours = [0, 1];
+
+    // other species are only avoided by default
+    assert_eq!(flock.species().len(), 2);
+    assert_eq!(flock.cohesion(0, &neighbours).mag(), 0.0);
+    assert_eq!(flock.align(0, &neighbours).mag(), 0.0);
+    assert!(flock.separate(0, &neighbours).mag() > 0.0);
+
+    flock
+        .set_interaction(0, 1, "ignore".parse().unwrap())
+        .unwrap();
+    assert_eq!(flock.separate(0, &neighbours).mag(), 0.0);
+
+    flock
+        .set_interaction(0, 1, "align+cohere".parse().unwrap())
+        .unwrap();
+    assert!(flock.cohesion(0, &neighbours).mag() > 0.0);
+    assert!(flock.align(0, &neighbours).mag() > 0.0);
+    assert_eq!(flock.separate(0, &neighbours).mag(), 0.0);
+
+    // the matrix is not symmetric
+    assert_eq!(flock.interactions().get(1, 0), Interaction::AVOID);
+    assert!(flock.set_interaction(0, 2, Interaction::FLOCK).is_err());
+    assert!("chase".parse::<Interaction>().is_err());
+
+    // species are untinted unless given a tint
+    let tint = Color::from_hex(0x3366cc80);
+    let tinted = flock.add_species(Species::new(1, Some(tint)));
+    assert!(flock.species()[0].tint.is_none());
+    assert_eq!(flock.species()[tinted].tint.unwrap().to_hex(), 0x3366cc80);
+}
+
+#[test]
+fn boids_arrive_at_the_cursor_and_attractors() {
+    let boid = test_boid(Vector2::new(300.0, 500.0), Vector2::new(0.0, 0.0));
+    let target = Vector2::new(700.0, 500.0);
+
+    let mut attracted = Flock::from_boids(vec![boid], AREA_SIZE);
+
+    let mut pulled = Flock::from_boids(vec![boid], AREA_SIZE);
+    pulled.add_attractor(Attractor::new(target, 2.0, 500.0));
+
+    for _ in 0..20000 {
+        attracted.update(0.001, AREA_SIZE, BORDER_THICK, &[Cursor::attract(target)]);
+        pulled.update(0.001, AREA_SIZE, BORDER_THICK, &[]);
+    }
+
+    // both settle at the target instead of flying past it
+    for flock in [attracted, pulled].iter() {
+        let boid = &flock.boids()[0];
+        assert!(boid.position.distance_to(&target) < 5.0);
+        assert!(boid.velocity.mag() < 5.0);
+    }
+
+    // an attractor does nothing to boids outside its radius
+    let mut far = Flock::from_boids(vec![boid], AREA_SIZE);
+    far.add_attractor(Attractor::new(target, 2.0, 100.0));
+    assert_eq!(far.attract(0).mag(), 0.0);
+}
+
+#[test]
+fn lone_boids_wander_reproducibly() {
+    let run = |wander_weight: f32| {
+        let boid = test_boid(Vector2::new(500.0, 500.0), Vector2::new(200.0, 0.0));
+        let mut flock = Flock::from_boids(vec![boid], AREA_SIZE);
+        let params = FlockParams {
+            wander_weight,
+            ..FlockParams::default()
+        };
+        flock.set_params(params).unwrap();
+
+        for _ in 0..2000 {
+            flock.update(0.001, AREA_SIZE, BORDER_THICK, &[]);
+        }
+        flock.boids()[0]
+    };
+
+    assert_eq!(run(0.0).velocity.y, 0.0);
+
+    let wandered = run(1.0);
+    assert!(wandered.velocity.y.abs() > 1.0);
+    assert_boids_close(&[wandered], &[run(1.0)], 0.0);
+}
+
+#[test]
+fn lone_boids_wander_the_same_at_any_index() {
+    // too far apart to see each other
+    let boids: Vec<Boid> = (0..16)
+        .map(|i| {
+            let position = Vector2::new(
+                200.0 + (i % 4) as f32 * 200.0,
+                200.0 + (i / 4) as f32 * 200.0,
+            );
+            test_boid(position, Vector2::new(20.0, 0.0))
+        })
+        .collect();
+    let params = FlockParams {
+        neighborhood_zone: 50.0,
+        separation_zone: 20.0,
+        ..FlockParams::default()
+    };
+
+    let mut original = Flock::from_boids(boids.clone(), AREA_SIZE);
+    original.set_params(params).unwrap();
+    let mut reversed = Flock::from_boids(boids.into_iter().rev().collect(), AREA_SIZE);
+    reversed.set_params(params).unwrap();
+
+    for _ in 0..500 {
+        original.update(0.01, AREA_SIZE, BORDER_THICK, &[]);
+        reversed.update(0.01, AREA_SIZE, BORDER_THICK, &[]);
+    }
+
+    let unreversed: Vec<Boid> = reversed.boids().iter().rev().copied().collect();
+    assert_boids_close(original.boids(), &unreversed, 0.0);
+    assert!(original.boids().iter().all(|boid| boid.wander_angle != 0.0));
+
+    // no jitter is drawn while wandering is off
+    let mut still = original.clone();
+    still
+        .set_params(FlockParams {
+            wander_weight: 0.0,
+            ..params
+        })
+        .unwrap();
+    still.update(0.01, AREA_SIZE, BORDER_THICK, &[]);
+    for (before, after) in original.boids().iter().zip(still.boids()) {
+        assert_eq!(before.wander_angle, after.wander_angle);
+    }
+}
+
+#[test]
+fn followers_trail_behind_their_leader() {
+    let boids = vec![
+        test_boid(Vector2::new(300.0, 500.0), Vector2::new(50.0, 0.0)),
+        test_boid(Vector2::new(380.0, 500.0), Vector2::new(0.0, 0.0)),
+    ];
+    let mut flock = Flock::from_boids(boids, AREA_SIZE);
+    let params = FlockParams {
+        wander_weight: 0.0,
+        ..FlockParams::default()
+    };
+    flock.set_params(params).unwrap();
+    flock.set_leader(0, true).unwrap();
+    assert_eq!(flock.leaders(), &[0]);
+
+    // the follower starts right in front of the leader
+    let leader = flock.closest_leader(1).unwrap();
+    assert_eq!(leader.id, 0);
+    assert!(flock.evade_leader(1, leader).mag() > 0.0);
+
+    for _ in 0..5000 {
+        flock.update(0.001, AREA_SIZE, BORDER_THICK, &[]);
+    }
+
+    let leader = &flock.boids()[0];
+    let follower = &flock.boids()[1];
+    let mut heading = leader.velocity;
+    heading.normalize();
+    let behind = leader.position - heading * params.leader_behind_distance;
+    assert!(follower.position.distance_to(&behind) < 20.0);
+
+    flock.set_leader(0, false).unwrap();
+    assert!(flock.leaders().is_empty());
+    assert!(flock.set_leader(2, true).is_err());
+}
+
+#[test]
+fn only_boids_near_a_leader_follow_it() {
+    let boids = vec![
+        test_boid(Vector2::new(150.0, 500.0), Vector2::new(50.0, 0.0)),
+        test_boid(Vector2::new(250.0, 500.0), Vector2::new(0.0, 50.0)),
+        test_boid(Vector2::new(800.0, 450.0), Vector2::new(50.0, 0.0)),
+        test_boid(Vector2::new(850.0, 520.0), Vector2::new(0.0, -50.0)),
+    ];
+    let plain = Flock::from_boids(boids, AREA_SIZE);
+    let mut led = plain.clone();
+    led.set_leader(0, true).unwrap();
+
+    let all: Vec<usize> = (0..4).collect();
+    let force = |flock: &Flock, index: usize| flock.flock(index, &all, &[]);
+
+    // out of the leader's neighbourhood the boids still flock with each other
+    for index in 2..4 {
+        assert!(force(&plain, index).distance_to(&force(&led, index)) <= 1e-6);
+    }
+    assert!(force(&plain, 1).distance_to(&force(&led, 1)) > 1e-3);
+}
+
+#[test]
+fn flock_patrols_a_closed_path() {
+    let boid = test_boid(Vector2::new(500.0, 500.0), Vector2::new(0.0, -100.0));
+    let mut flock = Flock::from_boids(vec![boid], AREA_SIZE);
+    let params = FlockParams {
+        wander_weight: 0.0,
+        ..FlockParams::default()
+    };
+    flock.set_params(params).unwrap();
+
+    let corners = vec![
+        Vector2::new(200.0, 200.0),
+        Vector2::new(800.0, 200.0),
+        Vector2::new(800.0, 800.0),
+        Vector2::new(200.0, 800.0),
+    ];
+    let path = Path::new(corners, 50.0, true);
+    flock.set_path(Some(path.clone()));
+
+    // after reaching the path it may only cut or overshoot the corners a little
+    let mut deviation = 0.0;
+    let mut visited_sides = [false; 4];
+    for tick in 0..30000 {
+        flock.update(0.001, AREA_SIZE, BORDER_THICK, &[]);
+        let position = flock.boids()[0].position;
+        if tick >= 5000 {
+            let (normal_point, _) = path.project(&position).unwrap();
+            deviation += position.distance_to(&normal_point) / 25000.0;
+        }
+        visited_sides[0] |= position.y < 250.0;
+        visited_sides[1] |= position.x > 750.0;
+        visited_sides[2] |= position.y > 750.0;
+        visited_sides[3] |= position.x < 250.0;
+    }
+    assert!(deviation < path.radius);
+    assert_eq!(visited_sides, [true; 4]);
+}
+
+#[test]
+fn flow_field_carries_the_boids() {
+    let boid = test_boid(Vector2::new(300.0, 500.0), Vector2::new(0.0, 0.0));
+    let mut flock = Flock::from_boids(vec![boid], AREA_SIZE);
+
+    // wind blowing right in the left half of the scene, left in the right half
+    let wind = FlowField::from_fn(AREA_SIZE, 100.0, |point| {
+        Vector2::new(if point.x < AREA_SIZE / 2.0 { 0.1 } else { -0.1 }, 0.0)
+    });
+    assert_eq!(wind.sample(&Vector2::new(-10.0, 20.0)).x, 0.1);
+    assert_eq!(wind.sample(&Vector2::new(990.0, 2000.0)).x, -0.1);
+    flock.set_flow_field(Some(wind));
+
+    assert_eq!(flock.current(0).x, 0.1);
+    for _ in 0..100 {
+        flock.update(0.001, AREA_SIZE, BORDER_THICK, &[]);
+    }
+    assert!(flock.boids()[0].velocity.x > 5.0);
+
+    // noise fields depend on the rng only
+    let noise = |seed: u64| FlowField::noise(AREA_SIZE, 50.0, 300.0, 0.1, &mut Rng::new(seed));
+    let (first, second, other) = (noise(7), noise(7), noise(8));
+    let mut differs = false;
+    for row in 0..first.columns() {
+        for column in 0..first.columns() {
+            let vector = first.get(column, row);
+            assert_eq!(vector.x, second.get(column, row).x);
+            assert_eq!(vector.y, second.get(column, row).y);
+            assert!((vector.mag() - 0.1).abs() < 1e-4);
+            differs |= vector.x != other.get(column, row).x;
+        }
+    }
+    assert!(differs);
+}
+
+#[test]
+fn fixed_timestep_catches_up_within_limits() {
+    let mut timestep = FixedTimestep::new(0.01, 5);
+
+    assert_eq!(timestep.advance(0.025), 2);
+    assert!((timestep.alpha() - 0.5).abs() < 1e-3);
+    assert_eq!(timestep.advance(0.005), 1);
+
+    // a long pause is not replayed step by step
+    assert_eq!(timestep.advance(10.0), 5);
+    assert!(timestep.alpha() < 1.0);
+    assert_eq!(timestep.advance(0.0), 0);
+    assert!(timestep.set_step(0.0).is_err());
+    assert!(timestep.set_max_steps(0).is_err());
+
+    // speeding up is not cut short by the limit, which is meant for real time
+    let mut timestep = FixedTimestep::new(0.001, 100);
+    assert_eq!(timestep.advance_scaled(0.02, 16.0), 320);
+    assert_eq!(timestep.advance_scaled(10.0, 16.0), 1600);
+    assert_eq!(timestep.advance_scaled(0.02, 0.5), 10);
+
+    // a scale above the maximum runs at the maximum, so the limit stays bounded
+    assert_eq!(timestep.advance_scaled(10.0, 1000.0), 1600);
+    assert_eq!(timestep.advance_scaled(0.02, f32::INFINITY), 320);
+
+    let boid = test_boid(Vector2::new(500.0, 500.0), Vector2::new(100.0, 0.0));
+    let mut flock = Flock::from_boids(vec![boid], AREA_SIZE);
+    flock.update(0.01, AREA_SIZE, BORDER_THICK, &[]);
+
+    let boid = &flock.boids()[0];
+    assert_eq!(boid.interpolated_position(0.0).x, 500.0);
+    assert_eq!(boid.interpolated_position(1.0).x, boid.position.x);
+    let halfway = boid.interpolated_position(0.5).x;
+    assert!(halfway > 500.0 && halfway < boid.position.x);
+}
+
+#[test]
+fn respawn_replaces_only_the_boids() {
+    let mut flock = Flock::new(20, AREA_SIZE, 7);
+    let fresh = flock.clone();
+    flock.spawn(5, 0).unwrap();
+    flock.set_leader(3, true).unwrap();
+    flock.add_obstacle(Obstacle::circle(Vector2::new(300.0, 300.0), 40.0));
+    flock.add_predator(Predator::new(&Vector2::new(100.0, 100.0), &mut Rng::new(1)));
+    for _ in 0..10 {
+        flock.update(DELTA_TIME, AREA_SIZE, BORDER_THICK, &[]);
+    }
+
+    flock.respawn(20, 7);
+    assert_boids_close(flock.boids(), fresh.boids(), 0.0);
+    assert!(flock.leaders().is_empty());
+    assert_eq!(flock.obstacles().len(), 1);
+    assert_eq!(flock.predators().len(), 1);
+}
+
+#[test]
+fn key_bindings_map_pressed_keys_to_actions() {
+    let event = |key: &str, pressed: bool| KeyEvent {
+        key: key.to_string(),
+        pressed,
+        time: 0.0,
+    };
+    let events = [
+        event(" ", true),
+        event(" ", false),
+        event("x", true),
+        event("d", true),
+    ];
+
+    let mut bindings = KeyBindings::default();
+    let actions: Vec<KeyAction> = bindings.actions(events.iter()).collect();
+    assert_eq!(actions, [KeyAction::TogglePause, KeyAction::ToggleDebug]);
+
+    bindings.bind("x", "spawn".parse().unwrap());
+    bindings.unbind("d");
+    let actions: Vec<KeyAction> = bindings.actions(events.iter()).collect();
+    assert_eq!(actions, [KeyAction::TogglePause, KeyAction::Spawn]);
+
+    // Caps Lock or Shift do not matter
+    assert_eq!(bindings.action("R"), Some(KeyAction::Reset));
+    bindings.bind("Q", KeyAction::Step);
+    assert_eq!(bindings.action("q"), Some(KeyAction::Step));
+    bindings.unbind("X");
+    assert_eq!(bindings.action("x"), None);
+
+    assert!("jump".parse::<KeyAction>().is_err());
+}
+
+#[test]
+fn every_cursor_acts_on_the_flock() {
+    let boid = test_boid(Vector2::new(500.0, 500.0), Vector2::new(0.0, 0.0));
+    let flock = Flock::from_boids(vec![boid], AREA_SIZE);
+
+    let attract = Cursor::attract(Vector2::new(700.0, 500.0));
+    let repel = Cursor::repel(Vector2::new(500.0, 480.0));
+
+    let attracted = flock.flock(0, &[], &[attract]);
+    assert!(attracted.x > 0.0);
+    assert_eq!(attracted.y, 0.0);
+
+    let both = flock.flock(0, &[], &[attract, repel]);
+    assert_eq!(both.x, attracted.x);
+    assert!(both.y > 0.0);
+}
+
+#[test]
+fn app_options_fill_defaults_and_reject_bad_fields() {
+    let options = AppOptions::from_json(
+        r##"{"canvas": "#left", "boids": 80, "boundary_mode": "reflect", "params": {"max_speed": 150}}"##,
+    )
+    .unwrap();
+    assert_eq!(options.canvas, "#left");
+    assert_eq!(options.boids, 80);
+    assert_eq!(options.boundary_mode, BoundaryMode::Reflect);
+    assert_eq!(options.params.max_speed, 150.0);
+    assert_eq!(
+        options.params.align_weight,
+        FlockParams::default().align_weight
+    );
+    assert_eq!(options.seed, AppOptions::default().seed);
+
+    let unknown = AppOptions::from_json(r#"{"boid_count": 80}"#).unwrap_err();
+    assert!(unknown.contains("boid_count"), "{}", unknown);
+
+    let mode = AppOptions::from_json(r#"{"boundary_mode": "bounce"}"#).unwrap_err();
+    assert!(mode.contains("bounce"), "{}", mode);
+
+    let params = AppOptions::from_json(r#"{"params": {"max_speed": -1}}"#).unwrap_err();
+    assert!(params.contains("max_speed"), "{}", params);
+
+    assert!(AppOptions::from_json(r#"{"boid_textures": []}"#).is_err());
+    assert!(AppOptions::from_json(r#"{"sprite_capacity": 0}"#).is_err());
+    // an obstacle circle must fit in one geometry batch
+    assert!(AppOptions::from_json(r#"{"geometry_capacity": 71}"#).is_err());
+    assert!(AppOptions::from_json(r#"{"geometry_capacity": 72}"#).is_ok());
+}
@@ -0,0 +1,77 @@
+//! The three rules as separate passes over the neighbours, the way they were written
+//! before `Flock::flock` gathered them in one loop. Shared by the tests and the bench.
+
+use flocking_simulation::flock_params::FlockParams;
+use flocking_simulation::flocking::Boid;
+use flocking_simulation::vector2::Vector2;
+
+fn steer_to(boid: &Boid, mut desired: Vector2<f32>, params: &FlockParams) -> Vector2<f32> {
+    desired.normalize();
+    desired *= params.max_speed;
+    let mut steer = desired - boid.velocity;
+    steer.limit(params.max_force);
+    steer
+}
+
+pub fn reference_cohesion(boids: &[Boid], current: usize, params: &FlockParams) -> Vector2<f32> {
+    let mut center_of_mass = Vector2::zero();
+    let mut count = 0;
+    for (index, boid) in boids.iter().enumerate() {
+        let distance = boid.position.distance_to(&boids[current].position);
+        if index != current && distance < params.neighborhood_zone && distance > 0.0 {
+            center_of_mass += boid.position;
+            count += 1;
+        }
+    }
+
+    if count == 0 {
+        return Vector2::zero();
+    }
+    center_of_mass /= count as f32;
+    steer_to(
+        &boids[current],
+        center_of_mass - boids[current].position,
+        params,
+    )
+}
+
+pub fn reference_separate(boids: &[Boid], current: usize, params: &FlockParams) -> Vector2<f32> {
+    let mut steer_vector = Vector2::zero();
+    let mut count = 0;
+    for (index, boid) in boids.iter().enumerate() {
+        let distance = boid.position.distance_to(&boids[current].position);
+        if index != current && distance < params.separation_zone && distance > 0.0 {
+            let mut diff = boids[current].position - boid.position;
+            diff.normalize();
+            diff /= distance;
+            steer_vector += diff;
+            count += 1;
+        }
+    }
+
+    if count > 0 {
+        steer_vector /= count as f32;
+    }
+    if steer_vector.mag() > 0.0 {
+        steer_vector = steer_to(&boids[current], steer_vector, params);
+    }
+    steer_vector
+}
+
+pub fn reference_align(boids: &[Boid], current: usize, params: &FlockParams) -> Vector2<f32> {
+    let mut average_velocity = Vector2::zero();
+    let mut count = 0;
+    for (index, boid) in boids.iter().enumerate() {
+        let distance = boid.position.distance_to(&boids[current].position);
+        if index != current && distance < params.neighborhood_zone && distance > 0.0 {
+            average_velocity += boid.velocity;
+            count += 1;
+        }
+    }
+
+    if count == 0 {
+        return Vector2::zero();
+    }
+    average_velocity /= count as f32;
+    steer_to(&boids[current], average_velocity, params)
+}
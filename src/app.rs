@@ -0,0 +1,974 @@
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+use wasm_bindgen::prelude::*;
+
+use swgl::camera2d::ratio_view::RatioView;
+use swgl::graphics_2d::color::Color;
+use swgl::graphics_2d::renderer::geometry_renderer::GeometryRenderer;
+use swgl::graphics_2d::renderer::rectangle_renderer::RectangleRenderer;
+
+use swgl::gl_wrapper::texture::texture_2d::Texture2D;
+use swgl::gl_wrapper::texture::texture_config::TextureConfiguration;
+use swgl::gl_wrapper::vertex_array_object::PrimitiveType;
+use swgl::global_tools::vector2::Vector2 as GlVector2;
+use swgl::graphics_2d::vertex_2d::predefined::color_vertex2d::ColorVertex2D;
+use swgl::resources_loader;
+
+use swgl::graphics_2d::vertex_2d::predefined::single_tex_vertex2d::SingleTexVertex2D;
+
+use swgl::gl_wrapper::basics::clear_canvas;
+
+use crate::app_options::AppOptions;
+use crate::app_state::InputState;
+use crate::attractor::Attractor;
+use crate::boundary::BoundaryMode;
+use crate::error::AppError;
+use crate::flock_params::FlockParams;
+use crate::flock_render::{
+    self, attractor_vertices, flow_field_vertices, leader_vertices, obstacle_vertices,
+    path_vertices, tint_vertices, view_cone_vertices,
+};
+use crate::flocking::{self, Cursor, Flock};
+use crate::flow_field::FlowField;
+use crate::gl_setup;
+use crate::keyboard::{KeyAction, KeyBindings, KeyEvent};
+use crate::obstacle::Obstacle;
+use crate::path::Path;
+use crate::rng::Rng;
+use crate::species::{Interaction, Species};
+use crate::timestep::{FixedTimestep, MAX_TIME_SCALE};
+use crate::vector2::Vector2;
+
+// -----------------------------------------------------------------------------------------
+
+const DISPLAY_SIZE: f32 = 1000.0;
+const BORDER_THICK: f32 = 50.0;
+const BORDER_COLOR: u32 = 0x222222ff;
+const OUTLINE_COLOR: u32 = 0xffffffff;
+const OBSTACLE_COLOR: u32 = 0x4a5568ff;
+const ATTRACTOR_COLOR: u32 = 0x63b3edff;
+const LEADER_COLOR: u32 = 0xf6ad5599;
+const PATH_COLOR: u32 = 0x9f7aeaff;
+const FLOW_FIELD_COLOR: u32 = 0x4fd1c580;
+const DEBUG_COLOR: u32 = 0xf6e05e80;
+const SPAWN_COUNT: usize = 10;
+const TIME_STEP: f32 = 0.001;
+const MAX_CATCH_UP_STEPS: u32 = 100;
+const MAX_FLOW_FIELD_COLUMNS: usize = 100;
+const MAX_KEY_EVENTS: usize = 256;
+
+// -----------------------------------------------------------------------------------------
+
+#[wasm_bindgen]
+pub struct AppState {
+    last_tick: f32,
+    timestep: FixedTimestep,
+    paused: bool,
+    time_scale: f32,
+
+    camera: RatioView,
+
+    flock: Flock,
+    batch_renderer: GeometryRenderer<ColorVertex2D>,
+    geometry_capacity: usize,
+    rectangle_renderer: RectangleRenderer<SingleTexVertex2D>,
+    sprite_capacity: usize,
+
+    boid_textures: Vec<Texture2D>,
+    predator_texture: Texture2D,
+    input: Rc<RefCell<InputState>>,
+    cursors: Vec<Cursor>,
+
+    debug_overlay: bool,
+    flow_field_overlay: bool,
+
+    key_bindings: KeyBindings,
+    key_events: VecDeque<KeyEvent>,
+    // what `reset_flock` starts again from
+    initial_boids: usize,
+    seed: u64,
+
+    // only held, the listeners are removed when the app is dropped
+    _event_listeners: gl_setup::EventListeners,
+    // fields drop in order, so the context outlives the renderers and textures using it
+    context: swgl::AppContext,
+}
+
+#[wasm_bindgen]
+impl AppState {
+    /// `options` is an optional object with any of the `AppOptions` fields.
+    #[wasm_bindgen(constructor)]
+    pub async fn new(
+        last_tick: f32,
+        width: f32,
+        height: f32,
+        options: JsValue,
+    ) -> Result<AppState, JsValue> {
+        #[cfg(feature = "console_error_panic_hook")]
+        console_error_panic_hook::set_once();
+        let options = parse_options(&options)?;
+
+        Ok(AppState::init(last_tick, width, height, options).await?)
+    }
+
+    /// Removes the event listeners and drops the renderers and textures, then the GL
+    /// context. The app holds no GL handles of its own: freeing the buffers, shaders and
+    /// textures is left to swgl's types when they are dropped. The app can not be used
+    /// afterwards, but a new one can be created on the same canvas.
+    pub fn destroy(self) {
+        drop(self);
+    }
+
+    pub fn update(&mut self, time: f32, width: f32, height: f32) -> Result<(), JsValue> {
+        self.camera
+            .update_canvas_size(GlVector2::new(width, height));
+
+        let now = time;
+        let elapsed = (now - self.last_tick) / 1000.0;
+        self.last_tick = now;
+
+        let key_events = self.input.borrow_mut().drain_key_events();
+        let actions: Vec<KeyAction> = self.key_bindings.actions(key_events.iter()).collect();
+        for action in actions {
+            self.run_key_action(action)?;
+        }
+        // kept for `drain_key_events`, the oldest go if JavaScript does not take them
+        self.key_events.extend(key_events);
+        let excess = self.key_events.len().saturating_sub(MAX_KEY_EVENTS);
+        self.key_events.drain(..excess);
+
+        // every pointer scares the flock away, or calls it while pressed
+        let input = self.input.borrow();
+        self.cursors.clear();
+        for pointer in input.pointers.values() {
+            let position = self
+                .camera
+                .map_pixel_coords_to_game_coords(&pointer.position);
+            let position = Vector2::new(position.x, position.y);
+            self.cursors.push(if pointer.pressed {
+                Cursor::attract(position)
+            } else {
+                Cursor::repel(position)
+            });
+        }
+        drop(input);
+
+        if !self.paused {
+            let steps = self.timestep.advance_scaled(elapsed, self.time_scale);
+            self.simulate(steps);
+        }
+
+        Ok(())
+    }
+
+    pub fn render(&mut self) -> Result<(), JsValue> {
+        Ok(self.draw_scene()?)
+    }
+
+    // ----------------------------- playback ---------------------------
+
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Simulates `steps` fixed steps right away, paused or not.
+    pub fn step(&mut self, steps: u32) {
+        self.simulate(steps);
+    }
+
+    pub fn time_scale(&self) -> f32 {
+        self.time_scale
+    }
+
+    /// Speed of the simulation relative to real time, 0.5 is half speed. At most
+    /// `MAX_TIME_SCALE`, so the catch-up limit stays bounded.
+    pub fn set_time_scale(&mut self, time_scale: f32) -> Result<(), JsValue> {
+        if !(0.0..=MAX_TIME_SCALE).contains(&time_scale) {
+            return Err(AppError::Config(format!(
+                "time scale must be between 0 and {}, got {}",
+                MAX_TIME_SCALE, time_scale
+            ))
+            .into());
+        }
+        self.time_scale = time_scale;
+        Ok(())
+    }
+
+    // ----------------------------- key bindings -----------------------
+
+    /// Binds a `KeyboardEvent.key` value to one of: pause, step, faster, slower,
+    /// reset_speed, reset, debug, spawn.
+    pub fn bind_key(&mut self, key: &str, action: &str) -> Result<(), JsValue> {
+        let action: KeyAction = action.parse().map_err(AppError::Config)?;
+        self.key_bindings.bind(key, action);
+        Ok(())
+    }
+
+    pub fn unbind_key(&mut self, key: &str) {
+        self.key_bindings.unbind(key);
+    }
+
+    /// Keys pressed and released since the last call, oldest first, as an array of
+    /// `{ key, pressed, time }` objects. Only the last `MAX_KEY_EVENTS` are kept.
+    pub fn drain_key_events(&mut self) -> Result<JsValue, JsValue> {
+        let events: Vec<KeyEvent> = self.key_events.drain(..).collect();
+        let json =
+            serde_json::to_string(&events).map_err(|err| AppError::Browser(err.to_string()))?;
+        js_sys::JSON::parse(&json)
+    }
+
+    // ----------------------------- time step --------------------------
+
+    /// Length of one simulation step, in seconds.
+    pub fn set_time_step(&mut self, seconds: f32) -> Result<(), JsValue> {
+        self.timestep.set_step(seconds).map_err(AppError::Config)?;
+        Ok(())
+    }
+
+    /// Most steps simulated per `update` call at normal speed, the rest of a longer pause
+    /// is skipped. The limit grows with the time scale.
+    pub fn set_max_catch_up_steps(&mut self, steps: u32) -> Result<(), JsValue> {
+        self.timestep
+            .set_max_steps(steps)
+            .map_err(AppError::Config)?;
+        Ok(())
+    }
+
+    // ----------------------------- flock params -----------------------
+
+    pub fn set_max_speed(&mut self, value: f32) -> Result<(), JsValue> {
+        self.change_flock_params(|params| params.max_speed = value)
+    }
+
+    pub fn set_max_force(&mut self, value: f32) -> Result<(), JsValue> {
+        self.change_flock_params(|params| params.max_force = value)
+    }
+
+    pub fn set_neighborhood_zone(&mut self, value: f32) -> Result<(), JsValue> {
+        self.change_flock_params(|params| params.neighborhood_zone = value)
+    }
+
+    pub fn set_separation_zone(&mut self, value: f32) -> Result<(), JsValue> {
+        self.change_flock_params(|params| params.separation_zone = value)
+    }
+
+    pub fn set_cohesion_weight(&mut self, value: f32) -> Result<(), JsValue> {
+        self.change_flock_params(|params| params.cohesion_weight = value)
+    }
+
+    pub fn set_separate_weight(&mut self, value: f32) -> Result<(), JsValue> {
+        self.change_flock_params(|params| params.separate_weight = value)
+    }
+
+    pub fn set_cursor_separate_weight(&mut self, value: f32) -> Result<(), JsValue> {
+        self.change_flock_params(|params| params.cursor_separate_weight = value)
+    }
+
+    pub fn set_cursor_attract_weight(&mut self, value: f32) -> Result<(), JsValue> {
+        self.change_flock_params(|params| params.cursor_attract_weight = value)
+    }
+
+    pub fn set_arrive_radius(&mut self, value: f32) -> Result<(), JsValue> {
+        self.change_flock_params(|params| params.arrive_radius = value)
+    }
+
+    pub fn set_align_weight(&mut self, value: f32) -> Result<(), JsValue> {
+        self.change_flock_params(|params| params.align_weight = value)
+    }
+
+    pub fn set_avoid_weight(&mut self, value: f32) -> Result<(), JsValue> {
+        self.change_flock_params(|params| params.avoid_weight = value)
+    }
+
+    pub fn set_obstacle_look_ahead(&mut self, value: f32) -> Result<(), JsValue> {
+        self.change_flock_params(|params| params.obstacle_look_ahead = value)
+    }
+
+    pub fn set_obstacle_margin(&mut self, value: f32) -> Result<(), JsValue> {
+        self.change_flock_params(|params| params.obstacle_margin = value)
+    }
+
+    pub fn set_predator_max_speed(&mut self, value: f32) -> Result<(), JsValue> {
+        self.change_flock_params(|params| params.predator_max_speed = value)
+    }
+
+    pub fn set_predator_max_force(&mut self, value: f32) -> Result<(), JsValue> {
+        self.change_flock_params(|params| params.predator_max_force = value)
+    }
+
+    pub fn set_flee_radius(&mut self, value: f32) -> Result<(), JsValue> {
+        self.change_flock_params(|params| params.flee_radius = value)
+    }
+
+    pub fn set_flee_weight(&mut self, value: f32) -> Result<(), JsValue> {
+        self.change_flock_params(|params| params.flee_weight = value)
+    }
+
+    pub fn set_boundary_margin(&mut self, value: f32) -> Result<(), JsValue> {
+        self.change_flock_params(|params| params.boundary_margin = value)
+    }
+
+    pub fn set_boundary_weight(&mut self, value: f32) -> Result<(), JsValue> {
+        self.change_flock_params(|params| params.boundary_weight = value)
+    }
+
+    pub fn set_wander_weight(&mut self, value: f32) -> Result<(), JsValue> {
+        self.change_flock_params(|params| params.wander_weight = value)
+    }
+
+    pub fn set_wander_distance(&mut self, value: f32) -> Result<(), JsValue> {
+        self.change_flock_params(|params| params.wander_distance = value)
+    }
+
+    pub fn set_wander_radius(&mut self, value: f32) -> Result<(), JsValue> {
+        self.change_flock_params(|params| params.wander_radius = value)
+    }
+
+    pub fn set_wander_jitter(&mut self, value: f32) -> Result<(), JsValue> {
+        self.change_flock_params(|params| params.wander_jitter = value)
+    }
+
+    pub fn set_follow_weight(&mut self, value: f32) -> Result<(), JsValue> {
+        self.change_flock_params(|params| params.follow_weight = value)
+    }
+
+    pub fn set_evade_leader_weight(&mut self, value: f32) -> Result<(), JsValue> {
+        self.change_flock_params(|params| params.evade_leader_weight = value)
+    }
+
+    pub fn set_leader_behind_distance(&mut self, value: f32) -> Result<(), JsValue> {
+        self.change_flock_params(|params| params.leader_behind_distance = value)
+    }
+
+    pub fn set_leader_sight_distance(&mut self, value: f32) -> Result<(), JsValue> {
+        self.change_flock_params(|params| params.leader_sight_distance = value)
+    }
+
+    pub fn set_leader_sight_radius(&mut self, value: f32) -> Result<(), JsValue> {
+        self.change_flock_params(|params| params.leader_sight_radius = value)
+    }
+
+    pub fn set_path_weight(&mut self, value: f32) -> Result<(), JsValue> {
+        self.change_flock_params(|params| params.path_weight = value)
+    }
+
+    pub fn set_path_look_ahead(&mut self, value: f32) -> Result<(), JsValue> {
+        self.change_flock_params(|params| params.path_look_ahead = value)
+    }
+
+    pub fn set_flow_weight(&mut self, value: f32) -> Result<(), JsValue> {
+        self.change_flock_params(|params| params.flow_weight = value)
+    }
+
+    /// One of `"wrap"`, `"reflect"`, `"steer"` or `"open"`.
+    pub fn set_boundary_mode(&mut self, mode: &str) -> Result<(), JsValue> {
+        let mode: BoundaryMode = mode.parse().map_err(AppError::Config)?;
+        self.flock.set_boundary_mode(mode);
+        Ok(())
+    }
+
+    // ----------------------------- predators ----------------------------
+
+    pub fn predator_count(&self) -> usize {
+        self.flock.predators().len()
+    }
+
+    pub fn set_predator_count(&mut self, count: usize) {
+        self.flock.set_predator_count(count);
+    }
+
+    // ----------------------------- species ------------------------------
+
+    /// Adds a species drawn with the given texture, an index into the `boid_textures`
+    /// option, and spawns `count` of its boids. An optional `tint`, RGBA like
+    /// `0x3366cc80`, is drawn as a disc under each of them. Returns the species id.
+    pub fn add_species(
+        &mut self,
+        texture: usize,
+        count: usize,
+        tint: Option<u32>,
+    ) -> Result<usize, JsValue> {
+        if texture >= self.boid_textures.len() {
+            return Err(AppError::Config(format!(
+                "species texture {} is out of range, {} boid textures are loaded",
+                texture,
+                self.boid_textures.len()
+            ))
+            .into());
+        }
+        let species_id = self.flock.add_species(Species::new(
+            texture,
+            tint.map(crate::color::Color::from_hex),
+        ));
+        self.flock
+            .spawn(count, species_id)
+            .map_err(AppError::Config)?;
+        Ok(species_id)
+    }
+
+    /// `interaction` is `flock`, `ignore`, or a `+` separated list of `align`, `cohere`
+    /// and `avoid`.
+    pub fn set_interaction(
+        &mut self,
+        observer: usize,
+        other: usize,
+        interaction: &str,
+    ) -> Result<(), JsValue> {
+        let interaction: Interaction = interaction.parse().map_err(AppError::Config)?;
+        self.flock
+            .set_interaction(observer, other, interaction)
+            .map_err(AppError::Config)?;
+        Ok(())
+    }
+
+    // ----------------------------- debug --------------------------------
+
+    pub fn set_debug_overlay(&mut self, enabled: bool) {
+        self.debug_overlay = enabled;
+    }
+
+    pub fn set_view_angle(&mut self, value: f32) -> Result<(), JsValue> {
+        self.change_flock_params(|params| params.view_angle = value)
+    }
+
+    pub fn set_flow_field_overlay(&mut self, enabled: bool) {
+        self.flow_field_overlay = enabled;
+    }
+
+    // ----------------------------- flow field ---------------------------
+
+    /// Same push everywhere in the scene.
+    pub fn set_wind(&mut self, x: f32, y: f32) -> Result<(), JsValue> {
+        check_point("wind", x, y)?;
+        let size = self.camera.scene_relative_size;
+        let wind = FlowField::from_fn(size, size, |_| Vector2::new(x, y));
+        self.flock.set_flow_field(Some(wind));
+        Ok(())
+    }
+
+    pub fn set_noise_flow_field(
+        &mut self,
+        cell_size: f32,
+        noise_scale: f32,
+        strength: f32,
+        seed: u32,
+    ) -> Result<(), JsValue> {
+        self.check_flow_field_cells(cell_size)?;
+        check_size("flow field noise scale", noise_scale)?;
+        if !strength.is_finite() {
+            return Err(AppError::Config(format!(
+                "flow field strength must be finite, got {}",
+                strength
+            ))
+            .into());
+        }
+        let flow_field = FlowField::noise(
+            self.camera.scene_relative_size,
+            cell_size,
+            noise_scale,
+            strength,
+            &mut Rng::new(seed as u64),
+        );
+        self.flock.set_flow_field(Some(flow_field));
+        Ok(())
+    }
+
+    /// `vectors` holds one flat `x, y` pair per cell, row by row.
+    pub fn set_flow_field(&mut self, cell_size: f32, vectors: &[f32]) -> Result<(), JsValue> {
+        self.check_flow_field_cells(cell_size)?;
+        let mut flow_field = FlowField::new(self.camera.scene_relative_size, cell_size);
+        let columns = flow_field.columns();
+        if vectors.len() != columns * columns * 2 {
+            return Err(AppError::Config(format!(
+                "expected {} values for a {}x{} flow field, got {}",
+                columns * columns * 2,
+                columns,
+                columns,
+                vectors.len()
+            ))
+            .into());
+        }
+
+        for (cell, pair) in vectors.chunks(2).enumerate() {
+            check_point("flow field vector", pair[0], pair[1])?;
+            flow_field.set(
+                cell % columns,
+                cell / columns,
+                Vector2::new(pair[0], pair[1]),
+            );
+        }
+        self.flock.set_flow_field(Some(flow_field));
+        Ok(())
+    }
+
+    pub fn clear_flow_field(&mut self) {
+        self.flock.set_flow_field(None);
+    }
+
+    // ----------------------------- obstacles ----------------------------
+
+    pub fn add_circle_obstacle(&mut self, x: f32, y: f32, radius: f32) -> Result<(), JsValue> {
+        check_point("obstacle position", x, y)?;
+        check_size("obstacle radius", radius)?;
+        self.flock
+            .add_obstacle(Obstacle::circle(Vector2::new(x, y), radius));
+        Ok(())
+    }
+
+    pub fn add_rectangle_obstacle(
+        &mut self,
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+    ) -> Result<(), JsValue> {
+        check_point("obstacle position", x, y)?;
+        check_size("obstacle width", width)?;
+        check_size("obstacle height", height)?;
+        self.flock.add_obstacle(Obstacle::rectangle(
+            Vector2::new(x, y),
+            Vector2::new(width, height),
+        ));
+        Ok(())
+    }
+
+    pub fn clear_obstacles(&mut self) {
+        self.flock.clear_obstacles();
+    }
+
+    // ----------------------------- path ---------------------------------
+
+    /// `points` holds the path as flat `x, y` pairs. The path is drawn in one batch, so
+    /// it has at most `geometry_capacity` points.
+    pub fn set_path(&mut self, points: &[f32], radius: f32, closed: bool) -> Result<(), JsValue> {
+        if points.len() < 4 || points.len() % 2 == 1 {
+            return Err(AppError::Config(
+                "path points must be at least two flat `x, y` pairs".to_string(),
+            )
+            .into());
+        }
+        if points.len() / 2 > self.geometry_capacity {
+            return Err(AppError::Config(format!(
+                "path has {} points, at most {} are allowed",
+                points.len() / 2,
+                self.geometry_capacity
+            ))
+            .into());
+        }
+        for pair in points.chunks(2) {
+            check_point("path point", pair[0], pair[1])?;
+        }
+        check_size("path radius", radius)?;
+
+        let points = points
+            .chunks(2)
+            .map(|pair| Vector2::new(pair[0], pair[1]))
+            .collect();
+        self.flock.set_path(Some(Path::new(points, radius, closed)));
+        Ok(())
+    }
+
+    pub fn clear_path(&mut self) {
+        self.flock.set_path(None);
+    }
+
+    // ----------------------------- leaders ------------------------------
+
+    pub fn promote_leader(&mut self, boid_index: usize) -> Result<(), JsValue> {
+        self.flock
+            .set_leader(boid_index, true)
+            .map_err(AppError::Config)?;
+        Ok(())
+    }
+
+    pub fn demote_leader(&mut self, boid_index: usize) -> Result<(), JsValue> {
+        self.flock
+            .set_leader(boid_index, false)
+            .map_err(AppError::Config)?;
+        Ok(())
+    }
+
+    // ----------------------------- attractors ---------------------------
+
+    pub fn add_attractor(
+        &mut self,
+        x: f32,
+        y: f32,
+        strength: f32,
+        radius: f32,
+    ) -> Result<(), JsValue> {
+        check_point("attractor position", x, y)?;
+        check_size("attractor strength", strength)?;
+        check_size("attractor radius", radius)?;
+        self.flock
+            .add_attractor(Attractor::new(Vector2::new(x, y), strength, radius));
+        Ok(())
+    }
+
+    pub fn clear_attractors(&mut self) {
+        self.flock.clear_attractors();
+    }
+}
+
+impl AppState {
+    async fn init(
+        last_tick: f32,
+        width: f32,
+        height: f32,
+        options: AppOptions,
+    ) -> Result<Self, AppError> {
+        // ----------------------------- init webgl ---------------------------
+
+        let input = Rc::new(RefCell::new(InputState::default()));
+        let (_, context, _event_listeners) = gl_setup::initialize_webgl_context(
+            &options.canvas,
+            Color::from_hex(options.clear_color),
+            &input,
+        )?;
+
+        // ----------------------------- load resources -----------------------
+
+        let mut file_paths: Vec<&str> = options.boid_textures.iter().map(|p| p.as_str()).collect();
+        file_paths.push(&options.predator_texture);
+        file_paths.sort_unstable();
+        file_paths.dedup();
+        let loaded_resource = resources_loader::get_files(&file_paths).await?;
+
+        // ----------------------------- prepare objects ----------------------
+
+        let camera = RatioView::new(GlVector2::new(width, height), DISPLAY_SIZE);
+
+        let load_texture = |path: &str| -> Result<Texture2D, AppError> {
+            let resource = loaded_resource
+                .get(path)
+                .ok_or_else(|| AppError::MissingResource(path.to_string()))?;
+            let image = resources_loader::unwrap_image_content(resource)?;
+            Ok(Texture2D::new_texture2d(
+                &context,
+                &image,
+                TextureConfiguration::default(),
+            )?)
+        };
+        let boid_textures = options
+            .boid_textures
+            .iter()
+            .map(|path| load_texture(path))
+            .collect::<Result<Vec<_>, _>>()?;
+        let predator_texture = load_texture(&options.predator_texture)?;
+
+        let batch_renderer = GeometryRenderer::init(&context, options.geometry_capacity)?;
+        let rectangle_renderer = RectangleRenderer::init(&context, options.sprite_capacity)?;
+
+        let mut flock =
+            flocking::Flock::new(options.boids, camera.scene_relative_size, options.seed);
+        flock.set_params(options.params).map_err(AppError::Config)?;
+        flock.set_boundary_mode(options.boundary_mode);
+
+        // ----------------------------- construct app ------------------------
+        Ok(Self {
+            context,
+            last_tick,
+            timestep: FixedTimestep::new(TIME_STEP, MAX_CATCH_UP_STEPS),
+            paused: false,
+            time_scale: 1.0,
+            camera,
+            flock,
+            batch_renderer,
+            geometry_capacity: options.geometry_capacity,
+            rectangle_renderer,
+            sprite_capacity: options.sprite_capacity,
+            boid_textures,
+            predator_texture,
+            input,
+            cursors: vec![],
+            debug_overlay: false,
+            flow_field_overlay: false,
+            key_bindings: KeyBindings::default(),
+            key_events: VecDeque::new(),
+            initial_boids: options.boids,
+            seed: options.seed,
+            _event_listeners,
+        })
+    }
+
+    fn simulate(&mut self, steps: u32) {
+        for _ in 0..steps {
+            self.flock.update(
+                self.timestep.step(),
+                self.camera.scene_relative_size,
+                BORDER_THICK,
+                &self.cursors,
+            );
+        }
+    }
+
+    fn run_key_action(&mut self, action: KeyAction) -> Result<(), AppError> {
+        match action {
+            KeyAction::TogglePause => self.paused = !self.paused,
+            KeyAction::Step => self.simulate(1),
+            KeyAction::Faster => self.time_scale = (self.time_scale * 2.0).min(MAX_TIME_SCALE),
+            KeyAction::Slower => self.time_scale /= 2.0,
+            KeyAction::ResetSpeed => self.time_scale = 1.0,
+            KeyAction::Reset => self.reset_flock(),
+            KeyAction::ToggleDebug => self.debug_overlay = !self.debug_overlay,
+            KeyAction::Spawn => self.flock.spawn(SPAWN_COUNT, 0).map_err(AppError::Config)?,
+        }
+        Ok(())
+    }
+
+    /// Starts over with the initial boids. Species, predators, obstacles, the path, the
+    /// flow field, attractors, interactions and params stay as they are.
+    fn reset_flock(&mut self) {
+        self.flock.respawn(self.initial_boids, self.seed);
+    }
+
+    fn draw_scene(&mut self) -> Result<(), AppError> {
+        clear_canvas(&self.context);
+        let alpha = self.timestep.alpha();
+
+        // tinted species and leaders stand out by a disc under their sprite
+        let tints = tint_vertices(&self.flock, alpha);
+        self.draw_in_batches(&tints, 3, || PrimitiveType::Triangles)?;
+        let halos = leader_vertices(&self.flock, alpha, Color::from_hex(LEADER_COLOR));
+        self.draw_in_batches(&halos, 3, || PrimitiveType::Triangles)?;
+
+        flock_render::draw(
+            &self.flock,
+            &self.context,
+            &mut self.rectangle_renderer,
+            &self.camera,
+            &self.boid_textures,
+            self.sprite_capacity,
+            alpha,
+        )?;
+        flock_render::draw_predators(
+            &self.flock,
+            &self.context,
+            &mut self.rectangle_renderer,
+            &self.camera,
+            &self.predator_texture,
+            self.sprite_capacity,
+            alpha,
+        )?;
+        for obstacle in self.flock.obstacles() {
+            self.batch_renderer.draw(
+                &self.context,
+                &obstacle_vertices(obstacle, Color::from_hex(OBSTACLE_COLOR)),
+                PrimitiveType::Triangles,
+                &self.camera,
+            )?;
+        }
+        if let Some(path) = self.flock.path() {
+            let primitive = if path.closed {
+                PrimitiveType::LineLoop
+            } else {
+                PrimitiveType::LineStrip
+            };
+            self.batch_renderer.draw(
+                &self.context,
+                &path_vertices(path, Color::from_hex(PATH_COLOR)),
+                primitive,
+                &self.camera,
+            )?;
+        }
+        for attractor in self.flock.attractors() {
+            self.batch_renderer.draw(
+                &self.context,
+                &attractor_vertices(attractor, Color::from_hex(ATTRACTOR_COLOR)),
+                PrimitiveType::LineLoop,
+                &self.camera,
+            )?;
+        }
+        if self.debug_overlay {
+            self.draw_debug_overlay()?;
+        }
+        if let (true, Some(flow_field)) = (self.flow_field_overlay, self.flock.flow_field()) {
+            let arrows = flow_field_vertices(flow_field, Color::from_hex(FLOW_FIELD_COLOR));
+            self.draw_in_batches(&arrows, 2, || PrimitiveType::Lines)?;
+        }
+        self.batch_renderer.draw(
+            &self.context,
+            &border_vertices(),
+            PrimitiveType::Triangles,
+            &self.camera,
+        )?;
+        self.batch_renderer.draw(
+            &self.context,
+            &outline_vertices(),
+            PrimitiveType::LineLoop,
+            &self.camera,
+        )?;
+        Ok(())
+    }
+
+    fn draw_debug_overlay(&mut self) -> Result<(), AppError> {
+        let color = Color::from_hex(DEBUG_COLOR);
+        for boid in self.flock.boids() {
+            self.batch_renderer.draw(
+                &self.context,
+                &view_cone_vertices(boid, self.flock.params(), color),
+                PrimitiveType::LineLoop,
+                &self.camera,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Draws separate triangles or lines of `primitive_size` vertices each, in as many
+    /// batches as the geometry renderer needs.
+    fn draw_in_batches<P>(
+        &mut self,
+        vertices: &[ColorVertex2D],
+        primitive_size: usize,
+        primitive: P,
+    ) -> Result<(), AppError>
+    where
+        P: Fn() -> PrimitiveType,
+    {
+        let batch = self.geometry_capacity / primitive_size * primitive_size;
+        for chunk in vertices.chunks(batch) {
+            self.batch_renderer
+                .draw(&self.context, chunk, primitive(), &self.camera)?;
+        }
+        Ok(())
+    }
+
+    /// The field must have at most `MAX_FLOW_FIELD_COLUMNS` columns of positive size.
+    fn check_flow_field_cells(&self, cell_size: f32) -> Result<(), AppError> {
+        check_size("flow field cell size", cell_size)?;
+        let columns = FlowField::column_count(self.camera.scene_relative_size, cell_size);
+        if columns > MAX_FLOW_FIELD_COLUMNS {
+            return Err(AppError::Config(format!(
+                "flow field cell size {} gives {} columns, at most {} are allowed",
+                cell_size, columns, MAX_FLOW_FIELD_COLUMNS
+            )));
+        }
+        Ok(())
+    }
+
+    fn change_flock_params<F>(&mut self, change: F) -> Result<(), JsValue>
+    where
+        F: FnOnce(&mut FlockParams),
+    {
+        let mut params = *self.flock.params();
+        change(&mut params);
+        self.flock.set_params(params).map_err(AppError::Config)?;
+        Ok(())
+    }
+}
+
+// -----------------------------------------------------------------------------------------
+
+fn check_point(what: &str, x: f32, y: f32) -> Result<(), AppError> {
+    if !x.is_finite() || !y.is_finite() {
+        return Err(AppError::Config(format!(
+            "{} must be finite, got ({}, {})",
+            what, x, y
+        )));
+    }
+    Ok(())
+}
+
+fn check_size(what: &str, value: f32) -> Result<(), AppError> {
+    if !value.is_finite() || value <= 0.0 {
+        return Err(AppError::Config(format!(
+            "{} must be a positive number, got {}",
+            what, value
+        )));
+    }
+    Ok(())
+}
+
+// I know, it is long :)
+fn border_vertices() -> [ColorVertex2D; 24] {
+    let color = Color::from_hex(BORDER_COLOR);
+
+    [
+        // left border
+        ColorVertex2D::new(GlVector2::new(0.0, 0.0), color, 0.0),
+        ColorVertex2D::new(GlVector2::new(BORDER_THICK, 0.0), color, 0.0),
+        ColorVertex2D::new(GlVector2::new(0.0, DISPLAY_SIZE), color, 0.0),
+        ColorVertex2D::new(GlVector2::new(0.0, DISPLAY_SIZE), color, 0.0),
+        ColorVertex2D::new(GlVector2::new(BORDER_THICK, 1000.0), color, 0.0),
+        ColorVertex2D::new(GlVector2::new(BORDER_THICK, 0.0), color, 0.0),
+        // right border
+        ColorVertex2D::new(GlVector2::new(DISPLAY_SIZE - BORDER_THICK, 0.0), color, 0.0),
+        ColorVertex2D::new(GlVector2::new(DISPLAY_SIZE, 0.0), color, 0.0),
+        ColorVertex2D::new(
+            GlVector2::new(DISPLAY_SIZE - BORDER_THICK, DISPLAY_SIZE),
+            color,
+            0.0,
+        ),
+        ColorVertex2D::new(
+            GlVector2::new(DISPLAY_SIZE - BORDER_THICK, DISPLAY_SIZE),
+            color,
+            0.0,
+        ),
+        ColorVertex2D::new(GlVector2::new(DISPLAY_SIZE, DISPLAY_SIZE), color, 0.0),
+        ColorVertex2D::new(GlVector2::new(DISPLAY_SIZE, 0.0), color, 0.0),
+        // top border
+        ColorVertex2D::new(GlVector2::new(0.0, 0.0), color, 0.0),
+        ColorVertex2D::new(GlVector2::new(0.0, BORDER_THICK), color, 0.0),
+        ColorVertex2D::new(GlVector2::new(DISPLAY_SIZE, 0.0), color, 0.0),
+        ColorVertex2D::new(GlVector2::new(DISPLAY_SIZE, 0.0), color, 0.0),
+        ColorVertex2D::new(GlVector2::new(1000.0, BORDER_THICK), color, 0.0),
+        ColorVertex2D::new(GlVector2::new(0.0, BORDER_THICK), color, 0.0),
+        // bottom border
+        ColorVertex2D::new(GlVector2::new(0.0, DISPLAY_SIZE - BORDER_THICK), color, 0.0),
+        ColorVertex2D::new(GlVector2::new(0.0, DISPLAY_SIZE), color, 0.0),
+        ColorVertex2D::new(
+            GlVector2::new(DISPLAY_SIZE, DISPLAY_SIZE - BORDER_THICK),
+            color,
+            0.0,
+        ),
+        ColorVertex2D::new(
+            GlVector2::new(DISPLAY_SIZE, DISPLAY_SIZE - BORDER_THICK),
+            color,
+            0.0,
+        ),
+        ColorVertex2D::new(GlVector2::new(DISPLAY_SIZE, DISPLAY_SIZE), color, 0.0),
+        ColorVertex2D::new(GlVector2::new(0.0, DISPLAY_SIZE), color, 0.0),
+    ]
+}
+
+fn outline_vertices() -> [ColorVertex2D; 4] {
+    let color = Color::from_hex(OUTLINE_COLOR);
+    [
+        ColorVertex2D::new(GlVector2::new(BORDER_THICK, BORDER_THICK), color, 0.0),
+        ColorVertex2D::new(
+            GlVector2::new(DISPLAY_SIZE - BORDER_THICK, BORDER_THICK),
+            color,
+            0.0,
+        ),
+        ColorVertex2D::new(
+            GlVector2::new(DISPLAY_SIZE - BORDER_THICK, DISPLAY_SIZE - BORDER_THICK),
+            color,
+            0.0,
+        ),
+        ColorVertex2D::new(
+            GlVector2::new(0.0 + BORDER_THICK, DISPLAY_SIZE - BORDER_THICK),
+            color,
+            0.0,
+        ),
+    ]
+}
+
+// -----------------------------------------------------------------------------------------
+
+fn parse_options(options: &JsValue) -> Result<AppOptions, AppError> {
+    if options.is_undefined() || options.is_null() {
+        return Ok(AppOptions::default());
+    }
+    let json: String = js_sys::JSON::stringify(options)?.into();
+    AppOptions::from_json(&json).map_err(AppError::Config)
+}
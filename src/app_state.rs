@@ -1,74 +1,50 @@
-use std::collections::VecDeque;
-use std::sync::Arc;
-use std::sync::Mutex;
+use std::collections::{HashMap, VecDeque};
 
 use swgl::global_tools::vector2::Vector2;
 
-// -----------------------------------------------------------------------------------------
-
-lazy_static! {
-    static ref APP_STATE: Mutex<Arc<AppState>> = Mutex::new(Arc::new(AppState::new()));
-}
+use crate::keyboard::KeyEvent;
 
 // -----------------------------------------------------------------------------------------
 
-pub fn update_dynamic_data(time: f32, canvas_height: f32, canvas_width: f32) {
-    let mut data = APP_STATE.lock().unwrap();
-    *data = Arc::new(AppState {
-        canvas_size: Vector2::new(canvas_height, canvas_width),
-        time,
-        keys: data.keys.clone(),
-        ..*data.clone()
-    });
+/// Mouse, pen or touch point over the canvas, in pixels.
+#[derive(Debug, Clone, Copy)]
+pub struct Pointer {
+    pub position: Vector2<f32>,
+    pub pressed: bool,
 }
 
-// -----------------------------------------------------------------------------------------
-
-pub fn get_curr_state() -> Arc<AppState> {
-    APP_STATE.lock().unwrap().clone()
-}
-
-// -----------------------------------------------------------------------------------------
-
-pub struct AppState {
-    pub canvas_size: Vector2<f32>,
-    pub mouse_pos: Vector2<f32>,
-    pub mouse_down: bool,
-    pub time: f32,
-    pub keys: VecDeque<String>,
+/// Input collected by the event listeners of one app between two updates. Each app owns
+/// its own, so several apps can run on one page.
+#[derive(Default)]
+pub struct InputState {
+    /// Active pointers by `PointerEvent.pointerId`.
+    pub pointers: HashMap<i32, Pointer>,
+    keys: VecDeque<KeyEvent>,
 }
 
-impl AppState {
-    fn new() -> Self {
-        Self {
-            canvas_size: Vector2::zero(),
-            mouse_pos: Vector2::zero(),
-            mouse_down: false,
-            time: 0.,
-            keys: VecDeque::new(),
+impl InputState {
+    /// Moves the pointer, adding it if it is new. `pressed` keeps its last value when `None`.
+    pub fn update_pointer(&mut self, id: i32, x: f32, y: f32, pressed: Option<bool>) {
+        let pointer = self.pointers.entry(id).or_insert(Pointer {
+            position: Vector2::zero(),
+            pressed: false,
+        });
+        pointer.position = Vector2::new(x, y);
+        if let Some(pressed) = pressed {
+            pointer.pressed = pressed;
         }
     }
-}
-
-// -----------------------------------------------------------------------------------------
 
-pub fn update_mouse_down(x: f32, y: f32, is_down: bool) {
-    let mut data = APP_STATE.lock().unwrap();
-    *data = Arc::new(AppState {
-        mouse_down: is_down,
-        mouse_pos: Vector2::new(x, y),
-        keys: data.keys.clone(),
-        ..*data.clone()
-    });
-}
+    pub fn remove_pointer(&mut self, id: i32) {
+        self.pointers.remove(&id);
+    }
 
-// -----------------------------------------------------------------------------------------
+    pub fn push_key_event(&mut self, key: String, pressed: bool, time: f32) {
+        self.keys.push_back(KeyEvent { key, pressed, time });
+    }
 
-pub fn update_mouse_position(x: f32, y: f32) {
-    let mut data = APP_STATE.lock().unwrap();
-    *data = Arc::new(AppState {
-        mouse_pos: Vector2::new(x, y),
-        keys: data.keys.clone(),
-        ..*data.clone()
-    });
+    /// Takes the keys pressed and released since the last call, oldest first.
+    pub fn drain_key_events(&mut self) -> VecDeque<KeyEvent> {
+        std::mem::take(&mut self.keys)
+    }
 }
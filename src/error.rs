@@ -0,0 +1,48 @@
+use std::fmt;
+
+use swgl::runtime_error::SWGLRuntimeError;
+use wasm_bindgen::JsValue;
+
+// -----------------------------------------------------------------------------------------
+
+/// Failure of the wasm app, handed to JavaScript as an `Error` instead of a panic.
+#[derive(Debug)]
+pub enum AppError {
+    /// WebGL setup, resource loading or drawing failed in swgl.
+    Graphics(SWGLRuntimeError),
+    /// A file the app needs was not among the loaded resources.
+    MissingResource(String),
+    /// The options or parameters given by JavaScript were rejected.
+    Config(String),
+    /// The browser refused a DOM call, like attaching an event listener.
+    Browser(String),
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AppError::Graphics(err) => write!(f, "graphics error: {:?}", err),
+            AppError::MissingResource(path) => write!(f, "missing resource `{}`", path),
+            AppError::Config(message) => write!(f, "invalid configuration: {}", message),
+            AppError::Browser(message) => write!(f, "browser error: {}", message),
+        }
+    }
+}
+
+impl From<SWGLRuntimeError> for AppError {
+    fn from(err: SWGLRuntimeError) -> Self {
+        AppError::Graphics(err)
+    }
+}
+
+impl From<JsValue> for AppError {
+    fn from(value: JsValue) -> Self {
+        AppError::Browser(value.as_string().unwrap_or_else(|| format!("{:?}", value)))
+    }
+}
+
+impl From<AppError> for JsValue {
+    fn from(err: AppError) -> Self {
+        js_sys::Error::new(&err.to_string()).into()
+    }
+}
@@ -0,0 +1,274 @@
+use swgl::camera2d::interface::CameraType;
+use swgl::gl_wrapper::texture::texture_2d::Texture2D;
+use swgl::global_tools::vector2::Vector2 as GlVector2;
+use swgl::graphics_2d::color::Color;
+use swgl::graphics_2d::renderer::rectangle_renderer::RectangleRenderer;
+use swgl::graphics_2d::vertex_2d::predefined::color_vertex2d::ColorVertex2D;
+use swgl::graphics_2d::vertex_2d::predefined::single_tex_vertex2d::SingleTexVertex2D;
+use swgl::runtime_error::SWGLResult;
+
+use crate::app_options::MIN_GEOMETRY_CAPACITY;
+use crate::attractor::Attractor;
+use crate::flock_params::FlockParams;
+use crate::flocking::{Boid, Flock};
+use crate::flow_field::FlowField;
+use crate::obstacle::Obstacle;
+use crate::path::Path;
+use crate::vector2::Vector2;
+
+// -----------------------------------------------------------------------------------------
+
+pub const BOID_SIZE_X: f32 = 21.0;
+pub const BOID_SIZE_Y: f32 = 35.0;
+pub const PREDATOR_SIZE_X: f32 = 32.0;
+pub const PREDATOR_SIZE_Y: f32 = 53.0;
+const CIRCLE_SEGMENTS: usize = 24;
+const VIEW_CONE_SEGMENTS: usize = 16;
+
+// an obstacle circle is the largest shape drawn in one batch
+const _: () = assert!(CIRCLE_SEGMENTS * 3 <= MIN_GEOMETRY_CAPACITY);
+
+// -----------------------------------------------------------------------------------------
+
+fn add_sprite(
+    renderer: &mut RectangleRenderer<SingleTexVertex2D>,
+    position: Vector2<f32>,
+    velocity: Vector2<f32>,
+    size_x: f32,
+    size_y: f32,
+) {
+    let vertices = SingleTexVertex2D::new_general(0.0, 1.0);
+    let angle = velocity.heading().to_degrees() - 90.0;
+    renderer.add_sprite_with_trans(
+        vertices,
+        &gl_vector(position),
+        &GlVector2::new(size_x, size_y),
+        &GlVector2::new(size_x / 2.0, size_y / 2.0),
+        angle.to_radians(),
+    );
+}
+
+/// Draws the boids with their species' texture, flushing every `capacity` sprites so the
+/// renderer never holds more than it was created for.
+pub fn draw(
+    flock: &Flock,
+    context: &swgl::AppContext,
+    renderer: &mut RectangleRenderer<SingleTexVertex2D>,
+    camera: &dyn CameraType,
+    textures: &[Texture2D],
+    capacity: usize,
+    alpha: f32,
+) -> SWGLResult<()> {
+    for (species_id, species) in flock.species().iter().enumerate() {
+        let texture = &textures[species.texture];
+        let boids: Vec<&Boid> = flock
+            .boids()
+            .iter()
+            .filter(|boid| boid.species == species_id)
+            .collect();
+
+        for chunk in boids.chunks(capacity) {
+            for boid in chunk {
+                let position = boid.interpolated_position(alpha);
+                add_sprite(renderer, position, boid.velocity, BOID_SIZE_X, BOID_SIZE_Y);
+            }
+            renderer.flush(context, camera, Some(texture))?;
+        }
+    }
+    Ok(())
+}
+
+/// Like `draw`, for the predators.
+pub fn draw_predators(
+    flock: &Flock,
+    context: &swgl::AppContext,
+    renderer: &mut RectangleRenderer<SingleTexVertex2D>,
+    camera: &dyn CameraType,
+    tex: &Texture2D,
+    capacity: usize,
+    alpha: f32,
+) -> SWGLResult<()> {
+    for chunk in flock.predators().chunks(capacity) {
+        for predator in chunk {
+            let position = predator.interpolated_position(alpha);
+            add_sprite(
+                renderer,
+                position,
+                predator.velocity,
+                PREDATOR_SIZE_X,
+                PREDATOR_SIZE_Y,
+            );
+        }
+        renderer.flush(context, camera, Some(tex))?;
+    }
+    Ok(())
+}
+
+// -----------------------------------------------------------------------------------------
+
+/// The simulation has its own vector type, so the renderer's one is built from it here.
+pub fn gl_vector(vector: Vector2<f32>) -> GlVector2<f32> {
+    GlVector2::new(vector.x, vector.y)
+}
+
+fn vertex(point: Vector2<f32>, color: Color) -> ColorVertex2D {
+    ColorVertex2D::new(gl_vector(point), color, 0.0)
+}
+
+/// Triangles filling the obstacle, for `PrimitiveType::Triangles`.
+pub fn obstacle_vertices(obstacle: &Obstacle, color: Color) -> Vec<ColorVertex2D> {
+    match obstacle {
+        Obstacle::Circle { center, radius } => {
+            let point = |segment: usize| {
+                let angle = segment as f32 / CIRCLE_SEGMENTS as f32 * std::f32::consts::PI * 2.0;
+                Vector2::new(
+                    center.x + angle.cos() * radius,
+                    center.y + angle.sin() * radius,
+                )
+            };
+
+            let mut vertices = Vec::with_capacity(CIRCLE_SEGMENTS * 3);
+            for segment in 0..CIRCLE_SEGMENTS {
+                vertices.push(vertex(*center, color));
+                vertices.push(vertex(point(segment), color));
+                vertices.push(vertex(point(segment + 1), color));
+            }
+            vertices
+        }
+        Obstacle::Rectangle { min, max } => vec![
+            vertex(Vector2::new(min.x, min.y), color),
+            vertex(Vector2::new(max.x, min.y), color),
+            vertex(Vector2::new(min.x, max.y), color),
+            vertex(Vector2::new(min.x, max.y), color),
+            vertex(Vector2::new(max.x, max.y), color),
+            vertex(Vector2::new(max.x, min.y), color),
+        ],
+    }
+}
+
+/// Points of the path, for `PrimitiveType::LineLoop` when it is closed and
+/// `PrimitiveType::LineStrip` otherwise.
+pub fn path_vertices(path: &Path, color: Color) -> Vec<ColorVertex2D> {
+    path.points()
+        .iter()
+        .map(|point| vertex(*point, color))
+        .collect()
+}
+
+fn disc_vertices(center: Vector2<f32>, radius: f32, color: Color) -> Vec<ColorVertex2D> {
+    obstacle_vertices(&Obstacle::circle(center, radius), color)
+}
+
+/// Halos drawn under all the leaders, for `PrimitiveType::Triangles`.
+pub fn leader_vertices(flock: &Flock, alpha: f32, color: Color) -> Vec<ColorVertex2D> {
+    flock
+        .leaders()
+        .iter()
+        .flat_map(|&index| {
+            let position = flock.boids()[index].interpolated_position(alpha);
+            disc_vertices(position, BOID_SIZE_Y * 0.75, color)
+        })
+        .collect()
+}
+
+/// Discs in their species' tint under the boids of tinted species, for
+/// `PrimitiveType::Triangles`.
+pub fn tint_vertices(flock: &Flock, alpha: f32) -> Vec<ColorVertex2D> {
+    flock
+        .boids()
+        .iter()
+        .filter_map(|boid| {
+            let tint = flock.species()[boid.species].tint?;
+            let position = boid.interpolated_position(alpha);
+            Some(disc_vertices(
+                position,
+                BOID_SIZE_Y / 2.0,
+                Color::from_hex(tint.to_hex()),
+            ))
+        })
+        .flatten()
+        .collect()
+}
+
+/// Circle around the area the attractor pulls boids in, for `PrimitiveType::LineLoop`.
+pub fn attractor_vertices(attractor: &Attractor, color: Color) -> Vec<ColorVertex2D> {
+    (0..CIRCLE_SEGMENTS)
+        .map(|segment| {
+            let angle = segment as f32 / CIRCLE_SEGMENTS as f32 * std::f32::consts::PI * 2.0;
+            let point = Vector2::new(
+                attractor.position.x + angle.cos() * attractor.radius,
+                attractor.position.y + angle.sin() * attractor.radius,
+            );
+            vertex(point, color)
+        })
+        .collect()
+}
+
+/// Outline of the area a boid perceives its neighbours in, for `PrimitiveType::LineLoop`.
+pub fn view_cone_vertices(boid: &Boid, params: &FlockParams, color: Color) -> Vec<ColorVertex2D> {
+    let radius = params.neighborhood_zone;
+    let half_angle = (params.view_angle / 2.0).to_radians();
+    let heading = boid.velocity.heading();
+
+    let mut vertices = Vec::with_capacity(VIEW_CONE_SEGMENTS + 2);
+    if params.view_angle < 360.0 {
+        vertices.push(vertex(boid.position, color));
+    }
+
+    for segment in 0..=VIEW_CONE_SEGMENTS {
+        let angle =
+            heading - half_angle + 2.0 * half_angle * segment as f32 / VIEW_CONE_SEGMENTS as f32;
+        let point = Vector2::new(
+            boid.position.x + angle.cos() * radius,
+            boid.position.y + angle.sin() * radius,
+        );
+        vertices.push(vertex(point, color));
+    }
+    vertices
+}
+
+/// An arrow per cell, for `PrimitiveType::Lines`. The longest vector of the field spans
+/// most of its cell, the others are scaled down in proportion.
+pub fn flow_field_vertices(flow_field: &FlowField, color: Color) -> Vec<ColorVertex2D> {
+    let columns = flow_field.columns();
+    let cells = (0..columns).flat_map(|row| (0..columns).map(move |column| (column, row)));
+
+    let longest = cells
+        .clone()
+        .map(|(column, row)| flow_field.get(column, row).mag())
+        .fold(0.0, f32::max);
+    if longest <= 0.0 {
+        return vec![];
+    }
+
+    let scale = flow_field.cell_size() * 0.8 / longest;
+    let mut vertices = Vec::with_capacity(columns * columns * 6);
+    for (column, row) in cells {
+        let vector = flow_field.get(column, row);
+        let length = vector.mag() * scale;
+        if length <= 0.0 {
+            continue;
+        }
+
+        let mut direction = vector;
+        direction.normalize();
+        let side = Vector2::new(-direction.y, direction.x);
+
+        let mut tail = flow_field.cell_center(column, row);
+        tail -= direction * (length / 2.0);
+        let mut tip = tail;
+        tip += direction * length;
+
+        let head = length * 0.3;
+        let mut left = tip - direction * head;
+        left += side * (head / 2.0);
+        let mut right = tip - direction * head;
+        right -= side * (head / 2.0);
+
+        for &(start, end) in [(tail, tip), (tip, left), (tip, right)].iter() {
+            vertices.push(vertex(start, color));
+            vertices.push(vertex(end, color));
+        }
+    }
+    vertices
+}
@@ -1,18 +1,23 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsCast;
 use wasm_bindgen::JsValue;
 use web_sys::*;
 
 use super::swgl::graphics_2d::color::Color;
-use super::swgl::runtime_error::SWGLResult;
 use super::swgl::utils::web_helpers;
+use crate::app_state::InputState;
+use crate::error::AppError;
 
 // -----------------------------------------------------------------------------------------
 
 pub fn initialize_webgl_context(
     canvas_selector: &str,
     clear_color: Color,
-) -> SWGLResult<(web_sys::HtmlCanvasElement, swgl::AppContext)> {
+    input: &Rc<RefCell<InputState>>,
+) -> Result<(web_sys::HtmlCanvasElement, swgl::AppContext, EventListeners), AppError> {
     let (canvas, context) = web_helpers::app_handler(canvas_selector)?;
 
     context.clear_color(
@@ -23,56 +28,142 @@ pub fn initialize_webgl_context(
     );
     context.clear_depth(1.);
 
-    attach_mouse_down_handler(&canvas).unwrap();
-    attach_mouse_up_handler(&canvas).unwrap();
-    attach_mouse_move_handler(&canvas).unwrap();
+    let mut listeners = EventListeners::default();
+    attach_pointer_handlers(&mut listeners, &canvas, input)?;
+    attach_key_handlers(&mut listeners, &canvas, input)?;
 
-    Ok((canvas, context))
+    Ok((canvas, context, listeners))
 }
 
 // -----------------------------------------------------------------------------------------
-// event handlers
 
-fn attach_mouse_down_handler(canvas: &HtmlCanvasElement) -> Result<(), JsValue> {
-    let handler = move |event: web_sys::MouseEvent| {
-        super::app_state::update_mouse_down(event.offset_x() as f32, event.offset_y() as f32, true);
-    };
+struct Listener {
+    target: EventTarget,
+    event_name: &'static str,
+    closure: Closure<dyn FnMut(Event)>,
+}
 
-    let handler = Closure::wrap(Box::new(handler) as Box<dyn FnMut(_)>);
-    canvas.add_event_listener_with_callback("mousedown", handler.as_ref().unchecked_ref())?;
-    handler.forget();
+/// Event listeners attached by the app. They stay attached as long as this handle lives
+/// and are removed when it is dropped, so another app can be created on the same page.
+#[derive(Default)]
+pub struct EventListeners {
+    listeners: Vec<Listener>,
+}
 
-    Ok(())
+impl EventListeners {
+    fn attach<E, F>(
+        &mut self,
+        target: &EventTarget,
+        event_name: &'static str,
+        mut handler: F,
+    ) -> Result<(), JsValue>
+    where
+        E: JsCast,
+        F: FnMut(E) + 'static,
+    {
+        let closure = Closure::wrap(
+            Box::new(move |event: Event| handler(event.unchecked_into())) as Box<dyn FnMut(_)>,
+        );
+        target.add_event_listener_with_callback(event_name, closure.as_ref().unchecked_ref())?;
+
+        self.listeners.push(Listener {
+            target: target.clone(),
+            event_name,
+            closure,
+        });
+        Ok(())
+    }
+}
+
+impl Drop for EventListeners {
+    fn drop(&mut self) {
+        for listener in self.listeners.drain(..) {
+            // the closure is freed right after, the browser must not call it anymore
+            let _ = listener.target.remove_event_listener_with_callback(
+                listener.event_name,
+                listener.closure.as_ref().unchecked_ref(),
+            );
+        }
+    }
 }
 
 // -----------------------------------------------------------------------------------------
+// event handlers
 
-fn attach_mouse_up_handler(canvas: &HtmlCanvasElement) -> Result<(), JsValue> {
-    let handler = move |event: web_sys::MouseEvent| {
-        super::app_state::update_mouse_down(
+fn attach_pointer_handlers(
+    listeners: &mut EventListeners,
+    canvas: &HtmlCanvasElement,
+    input: &Rc<RefCell<InputState>>,
+) -> Result<(), JsValue> {
+    let state = input.clone();
+    listeners.attach(canvas, "pointerdown", move |event: PointerEvent| {
+        state.borrow_mut().update_pointer(
+            event.pointer_id(),
             event.offset_x() as f32,
             event.offset_y() as f32,
-            false,
+            Some(true),
         );
-    };
+    })?;
 
-    let handler = Closure::wrap(Box::new(handler) as Box<dyn FnMut(_)>);
-    canvas.add_event_listener_with_callback("mouseup", handler.as_ref().unchecked_ref())?;
-    handler.forget();
+    let state = input.clone();
+    listeners.attach(canvas, "pointermove", move |event: PointerEvent| {
+        state.borrow_mut().update_pointer(
+            event.pointer_id(),
+            event.offset_x() as f32,
+            event.offset_y() as f32,
+            None,
+        );
+    })?;
+
+    // a lifted finger is gone, a released mouse button still hovers over the canvas
+    let state = input.clone();
+    listeners.attach(canvas, "pointerup", move |event: PointerEvent| {
+        let mut state = state.borrow_mut();
+        if event.pointer_type() == "mouse" {
+            state.update_pointer(
+                event.pointer_id(),
+                event.offset_x() as f32,
+                event.offset_y() as f32,
+                Some(false),
+            );
+        } else {
+            state.remove_pointer(event.pointer_id());
+        }
+    })?;
+
+    for &event_name in ["pointercancel", "pointerleave"].iter() {
+        let state = input.clone();
+        listeners.attach(canvas, event_name, move |event: PointerEvent| {
+            state.borrow_mut().remove_pointer(event.pointer_id());
+        })?;
+    }
 
     Ok(())
 }
 
 // -----------------------------------------------------------------------------------------
 
-fn attach_mouse_move_handler(canvas: &HtmlCanvasElement) -> Result<(), JsValue> {
-    let handler = move |event: web_sys::MouseEvent| {
-        super::app_state::update_mouse_position(event.offset_x() as f32, event.offset_y() as f32);
-    };
-
-    let handler = Closure::wrap(Box::new(handler) as Box<dyn FnMut(_)>);
-    canvas.add_event_listener_with_callback("mousemove", handler.as_ref().unchecked_ref())?;
-    handler.forget();
+fn attach_key_handlers(
+    listeners: &mut EventListeners,
+    canvas: &HtmlCanvasElement,
+    input: &Rc<RefCell<InputState>>,
+) -> Result<(), JsValue> {
+    // keys go to the focused canvas only, so apps sharing a page are driven separately
+    canvas.set_tab_index(0);
+    canvas.focus()?;
+
+    for &(event_name, pressed) in [("keydown", true), ("keyup", false)].iter() {
+        let state = input.clone();
+        listeners.attach(canvas, event_name, move |event: KeyboardEvent| {
+            // held keys repeat their keydown, only the first one counts
+            if event.repeat() {
+                return;
+            }
+            state
+                .borrow_mut()
+                .push_key_event(event.key(), pressed, event.time_stamp() as f32);
+        })?;
+    }
 
     Ok(())
 }
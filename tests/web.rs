@@ -5,9 +5,38 @@
 extern crate wasm_bindgen_test;
 use wasm_bindgen_test::*;
 
+use flocking_alg_with_textures_usage::AppState;
+
 wasm_bindgen_test_configure!(run_in_browser);
 
+// a white pixel, so the test does not depend on the files served next to it
+const TEXTURE: &str = "data:image/png;base64,\
+iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAYAAAAfFcSJAAAAC0lEQVR42mP4DwQACfsD/Wj6HMwAAAAASUVORK5CYII=";
+
 #[wasm_bindgen_test]
 fn pass() {
     assert_eq!(1 + 1, 2);
 }
+
+#[wasm_bindgen_test]
+async fn app_starts_again_after_destroy() {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let canvas = document.create_element("canvas").unwrap();
+    canvas.set_id("destroy-test");
+    document.body().unwrap().append_child(&canvas).unwrap();
+
+    let options = format!(
+        r##"{{"canvas": "#destroy-test", "boid_textures": ["{0}"], "predator_texture": "{0}"}}"##,
+        TEXTURE
+    );
+    let options = js_sys::JSON::parse(&options).unwrap();
+
+    for _ in 0..2 {
+        let mut app = AppState::new(0.0, 300.0, 150.0, options.clone())
+            .await
+            .unwrap();
+        app.update(16.0, 300.0, 150.0).unwrap();
+        app.render().unwrap();
+        app.destroy();
+    }
+}
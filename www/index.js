@@ -32,69 +32,97 @@ const updateCanvasParams = () => {
 };
 
 // game
-(async () => {
-  try {
-    const engine = await import("wasm-app");
+const MyProject = {};
 
-    if (!fullscreenMode) setInitialCanvasParams();
-    else updateCanvasParams();
-
-    // Project namespace
-    const MyProject = {};
-    MyProject.lastTick = performance.now();
-    MyProject.lastRender = MyProject.lastTick;
-    MyProject.tickLength = 1.0;
-
-    const appState = await new engine.AppState(
-      MyProject.lastTick,
-      canvas.clientWidth,
-      canvas.clientHeight
-    );
-
-    const render = (tFrame) => {
-      // you can use stopMain as ID to stop project loop
-      MyProject.stopMain = window.requestAnimationFrame(render);
-      let nextTick = MyProject.lastTick + MyProject.tickLength;
-      let numTicks = 0;
-
-      if (tFrame > nextTick) {
-        let timeSinceTick = tFrame - MyProject.lastTick;
-        numTicks = Math.floor(timeSinceTick / MyProject.tickLength);
-      }
+const stop = () => {
+  if (MyProject.stopMain !== undefined) {
+    window.cancelAnimationFrame(MyProject.stopMain);
+    MyProject.stopMain = undefined;
+  }
+  if (MyProject.appState !== undefined) {
+    // removes the event listeners and frees the GL resources
+    MyProject.appState.destroy();
+    MyProject.appState = undefined;
+  }
+};
 
-      // update
-      queueUpdates(numTicks);
+// the app reports failures as errors, show them next to the canvas
+const errorMessage = document.getElementById("error");
 
-      // render
-      appState.render(); // tFrame
-
-      MyProject.lastRender = tFrame;
-    };
-
-    let lastUpdate = Date.now();
-
-    const queueUpdates = (numTicks) => {
-      for (let i = 0; i < numTicks; ++i) {
-        MyProject.lastTick = MyProject.lastTick + MyProject.tickLength;
-
-        if (
-          fullscreenMode &&
-          (window.innerHeight != canvas.height ||
-            window.innerWidth != canvas.width)
-        ) {
-          updateCanvasParams();
-        }
-
-        appState.update(
-          MyProject.lastTick,
-          canvas.clientWidth,
-          canvas.clientHeight
-        );
+const showError = (err) => {
+  stop();
+  console.error(err);
+  errorMessage.textContent = `The simulation stopped: ${err.message || err}`;
+  errorMessage.hidden = false;
+};
+
+const hideError = () => {
+  errorMessage.hidden = true;
+  errorMessage.textContent = "";
+};
+
+const start = async (engine) => {
+  stop();
+  hideError();
+
+  if (!fullscreenMode) setInitialCanvasParams();
+  else updateCanvasParams();
+
+  MyProject.lastTick = performance.now();
+  MyProject.lastRender = MyProject.lastTick;
+
+  // any AppOptions field can be set here, the others keep their defaults
+  const appState = await new engine.AppState(
+    MyProject.lastTick,
+    canvas.clientWidth,
+    canvas.clientHeight,
+    { canvas: "#canvas", boids: 50 }
+  );
+  MyProject.appState = appState;
+
+  const render = (tFrame) => {
+    // you can use stopMain as ID to stop project loop
+    MyProject.stopMain = window.requestAnimationFrame(render);
+
+    if (
+      fullscreenMode &&
+      (window.innerHeight != canvas.height ||
+        window.innerWidth != canvas.width)
+    ) {
+      updateCanvasParams();
+    }
+
+    try {
+      // update, the simulation runs as many fixed steps as the elapsed time needs
+      if (tFrame !== undefined) {
+        MyProject.lastTick = tFrame;
+        appState.update(tFrame, canvas.clientWidth, canvas.clientHeight);
       }
-    };
 
-    render();
+      // render
+      appState.render();
+    } catch (err) {
+      showError(err);
+      return;
+    }
+
+    MyProject.lastRender = tFrame;
+  };
+
+  render();
+};
+
+(async () => {
+  try {
+    const engine = await import("wasm-app");
+
+    // start again to re-create the app, stop to tear it down
+    MyProject.start = () => start(engine).catch(showError);
+    MyProject.stop = stop;
+    window.MyProject = MyProject;
+
+    await MyProject.start();
   } catch (err) {
-    console.log(err);
+    showError(err);
   }
 })();